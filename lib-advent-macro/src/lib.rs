@@ -1,5 +1,6 @@
 use proc_macro::TokenStream;
-use quote::quote;
+use quote::{format_ident, quote};
+use syn::{Data, DeriveInput, Fields, LitStr};
 
 /// ```
 /// //#[derive(Parse, ParseFromStr, PartialEq, Eq, Debug)]
@@ -9,14 +10,34 @@ use quote::quote;
 /// //    statuses: Vec<String>,
 /// //}
 /// ```
-#[proc_macro_derive(ParseFromStr)]
+///
+/// Enums don't need the `Parse` derive; every variant gets a `prse` pattern of its own, tried in
+/// order. Tuple fields are referred to by position, named fields by name.
+///
+/// ```
+/// //#[derive(ParseFromStr, PartialEq, Eq, Debug)]
+/// //enum Instruction {
+/// //    #[pattern("L{0}")]
+/// //    Left(i32),
+/// //    #[pattern("move {count} from {from}")]
+/// //    Move { count: usize, from: String },
+/// //    #[pattern("noop")]
+/// //    Noop,
+/// //}
+/// ```
+#[proc_macro_derive(ParseFromStr, attributes(pattern))]
 pub fn hello_macro_derive(input: TokenStream) -> TokenStream {
     // Construct a representation of Rust code as a syntax tree
     // that we can manipulate
-    let ast = syn::parse(input).unwrap();
+    let ast: DeriveInput = syn::parse(input).unwrap();
 
     // Build the trait implementation
-    impl_parse_from_str_macro(&ast)
+    match &ast.data {
+        Data::Enum(_) => impl_parse_enum_from_str_macro(&ast)
+            .unwrap_or_else(|it| it.to_compile_error())
+            .into(),
+        _ => impl_parse_from_str_macro(&ast),
+    }
 }
 
 fn impl_parse_from_str_macro(ast: &syn::DeriveInput) -> TokenStream {
@@ -32,3 +53,92 @@ fn impl_parse_from_str_macro(ast: &syn::DeriveInput) -> TokenStream {
     };
     generated.into()
 }
+
+fn impl_parse_enum_from_str_macro(ast: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let name = &ast.ident;
+    let Data::Enum(data) = &ast.data else {
+        unreachable!()
+    };
+    let mut attempts = vec![];
+    let mut tried = vec![];
+    for variant in &data.variants {
+        let ident = &variant.ident;
+        let attr = variant
+            .attrs
+            .iter()
+            .find(|it| it.path().is_ident("pattern"))
+            .ok_or_else(|| syn::Error::new_spanned(ident, "Missing #[pattern(\"...\")]"))?;
+        let pattern: LitStr = attr.parse_args()?;
+        tried.push(format!("{} ({:?})", ident, pattern.value()));
+
+        let types = variant.fields.iter().map(|it| &it.ty).collect::<Vec<_>>();
+        let values = (0..types.len())
+            .map(|it| format_ident!("value_{}", it))
+            .collect::<Vec<_>>();
+        let (pattern, construct) = match &variant.fields {
+            Fields::Named(fields) => {
+                let names = fields
+                    .named
+                    .iter()
+                    .map(|it| it.ident.clone().unwrap())
+                    .collect::<Vec<_>>();
+                let positional = positional_pattern(&pattern.value(), &names);
+                (
+                    LitStr::new(&positional, pattern.span()),
+                    quote!(#name::#ident { #(#names: #values),* }),
+                )
+            }
+            Fields::Unnamed(_) => (pattern, quote!(#name::#ident(#(#values),*))),
+            Fields::Unit => (pattern, quote!(#name::#ident)),
+        };
+        attempts.push(quote! {
+            let parsed: Result<(#(#types),*), prse::ParseError> = prse::try_parse!(s, #pattern);
+            if let Ok((#(#values),*)) = parsed {
+                return Ok(#construct);
+            }
+        });
+    }
+    let tried = tried.join(", ");
+
+    Ok(quote! {
+        impl ::std::str::FromStr for #name {
+            type Err = prse::ParseError;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                #(#attempts)*
+                Err(prse::ParseError::Other(format!(
+                    "{:?} matches none of the variants {}",
+                    s, #tried
+                )))
+            }
+        }
+    })
+}
+
+/// Replaces every `{field}` (also with a `:`-separated repetition) by `{index}` of the field.
+fn positional_pattern(pattern: &str, names: &[syn::Ident]) -> String {
+    let mut result = String::new();
+    let mut rest = pattern;
+    while let Some(open) = rest.find('{') {
+        result.push_str(&rest[..open]);
+        rest = &rest[open..];
+        if rest.starts_with("{{") {
+            result.push_str("{{");
+            rest = &rest[2..];
+            continue;
+        }
+        // Unclosed braces are left for prse to complain about
+        let Some(close) = rest.find('}') else {
+            break;
+        };
+        let placeholder = &rest[1..close];
+        let (var, repetition) = placeholder.split_at(placeholder.find(':').unwrap_or(close - 1));
+        match names.iter().position(|it| it == var.trim()) {
+            Some(index) => result.push_str(&format!("{{{}{}}}", index, repetition)),
+            None => result.push_str(&rest[..=close]),
+        }
+        rest = &rest[close + 1..];
+    }
+    result.push_str(rest);
+    result
+}
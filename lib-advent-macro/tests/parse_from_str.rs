@@ -0,0 +1,40 @@
+use lib_advent_macro::ParseFromStr;
+
+#[derive(ParseFromStr, Debug, PartialEq, Eq)]
+enum Instruction {
+    #[pattern("L{0}")]
+    Left(i32),
+    #[pattern("move {count} from {from}")]
+    Move { count: usize, from: String },
+    #[pattern("swap {1} with {0}")]
+    Swap(char, char),
+    #[pattern("noop")]
+    Noop,
+}
+
+#[test]
+fn parses_enums_by_variant_pattern() {
+    assert_eq!("L42".parse(), Ok(Instruction::Left(42)));
+    assert_eq!(
+        "move 3 from stack".parse(),
+        Ok(Instruction::Move {
+            count: 3,
+            from: "stack".to_string()
+        })
+    );
+    assert_eq!("swap a with b".parse(), Ok(Instruction::Swap('b', 'a')));
+    assert_eq!("noop".parse(), Ok(Instruction::Noop));
+}
+
+#[test]
+fn lists_every_variant_tried_on_failure() {
+    let error = "Lx".parse::<Instruction>().unwrap_err();
+
+    assert_eq!(
+        error.to_string(),
+        concat!(
+            r#""Lx" matches none of the variants Left ("L{0}"), "#,
+            r#"Move ("move {count} from {from}"), Swap ("swap {1} with {0}"), Noop ("noop")"#
+        )
+    );
+}
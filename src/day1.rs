@@ -1,16 +1,12 @@
 use crate::util::AdventHelper;
+use lib_advent_macro::ParseFromStr;
 
 pub fn main() {
     let advent = AdventHelper::from_file_name(file!());
     let lines: Vec<i32> = advent
         .parse_from_strings()
         .into_iter()
-        .map(|it: String| {
-            it.replace("R", "+")
-                .replace("L", "-")
-                .parse::<i32>()
-                .unwrap()
-        })
+        .map(Rotation::offset)
         .collect();
 
     advent.part1("Result: {}", part1(&lines));
@@ -53,3 +49,20 @@ fn part2(lines: &[i32]) -> usize {
     });
     count
 }
+
+#[derive(ParseFromStr, Debug, PartialEq, Eq, Clone, Copy)]
+enum Rotation {
+    #[pattern("L{0}")]
+    Left(i32),
+    #[pattern("R{0}")]
+    Right(i32),
+}
+
+impl Rotation {
+    fn offset(self) -> i32 {
+        match self {
+            Rotation::Left(it) => -it,
+            Rotation::Right(it) => it,
+        }
+    }
+}
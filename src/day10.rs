@@ -1,10 +1,7 @@
 use crate::util::AdventHelper;
+use crate::util::gf2::Gf2System;
+use crate::util::ilp::IntegerProgram;
 use itertools::Itertools;
-use lib_advent_macro::ParseFromStr;
-use prse::Parse;
-use std::collections::{HashMap};
-use std::str::FromStr;
-use pathfinding::prelude::{bfs, dijkstra};
 
 pub fn main() {
     let advent = AdventHelper::from_file_name(file!());
@@ -14,29 +11,37 @@ pub fn main() {
         .collect_vec();
 
     advent.part1("Result: {}", part1(&machines));
+    advent.part2("Result: {}", part2(&machines));
 }
 
 fn part1(machines: &[Machine]) -> usize {
-    machines.iter().map(|it| number_of_presses(it)).sum()
+    machines.iter().map(number_of_presses).sum()
 }
 
 fn number_of_presses(machine: &Machine) -> usize {
-    let n = machine.target.len();
-    let initial_state = vec![false; n];
-    let path = bfs(&initial_state, |it| succ(it, &machine.buttons), |it| it == &machine.target).unwrap();
-    path.len() - 1
+    let mut system = Gf2System::new(machine.buttons.len());
+    for (light, on) in machine.target.iter().enumerate() {
+        let toggling = machine.buttons.iter().positions(|it| it.contains(&light));
+        system.add_equation(toggling, *on);
+    }
+    system.solve().unwrap().min_weight().count_ones()
 }
 
-fn succ(state: &Vec<bool>, buttons: &[Vec<usize>]) -> Vec<Vec<bool>> {
-    buttons.iter().map(|it| apply_button(state, it)).collect_vec()
+fn part2(machines: &[Machine]) -> i64 {
+    machines.iter().map(number_of_joltage_presses).sum()
 }
 
-fn apply_button(state: &Vec<bool>, button: &Vec<usize> ) -> Vec<bool> {
-    let mut new_state = state.clone();
-    for i in button {
-        new_state[*i] = !new_state[*i];
+fn number_of_joltage_presses(machine: &Machine) -> i64 {
+    let mut program = IntegerProgram::new(machine.buttons.len());
+    for (counter, req) in machine.joltage_req.iter().enumerate() {
+        let coefficients = machine
+            .buttons
+            .iter()
+            .map(|it| i64::from(it.contains(&counter)))
+            .collect_vec();
+        program.add_equation(coefficients, *req as i64);
     }
-    new_state
+    program.minimize().unwrap().cost
 }
 
 fn parse(input: &[String]) -> Machine {
@@ -1,52 +1,58 @@
 use crate::util::AdventHelper;
-use lib_advent_macro::ParseFromStr;
-use prse::Parse;
-use std::collections::HashSet;
-use std::str::FromStr;
+use crate::util::interval::{Interval, IntervalSet};
+use crate::util::numtheory::{
+    block_repunit, digit_count, divisors, mobius, pow10, sum_of_multiples,
+};
 
 pub fn main() {
     let advent = AdventHelper::from_file_name(file!());
-    let mut ranges: Vec<Input> = advent.parse_sequence(",");
-    ranges.sort_by_key(|it| it.min);
+    let ranges: Vec<Interval<u64>> = advent.parse_sequence(",");
+    let ranges: IntervalSet<u64> = ranges.into_iter().collect();
 
     advent.part1("Result: {}", part1(&ranges));
     advent.part2("Result: {}", part2(&ranges));
 }
 
-fn part1(ranges: &[Input]) -> u64 {
-    invalid_ids(ranges, 2).iter().sum()
+fn part1(ranges: &IntervalSet<u64>) -> u64 {
+    invalid_id_sum(ranges, |len| {
+        if len.is_multiple_of(2) {
+            vec![(len / 2, 1)]
+        } else {
+            vec![]
+        }
+    })
 }
 
-fn part2(ranges: &[Input]) -> u64 {
-    let max = ranges.iter().map(|it| it.max).max().unwrap();
-
-    let ids: HashSet<u64> = (2..max.to_string().len())
-        .flat_map(|it| invalid_ids(ranges, it))
-        .collect();
-    ids.iter().sum()
+fn part2(ranges: &IntervalSet<u64>) -> u64 {
+    // Repeating blocks of length d and e also means repeating blocks of length gcd(d, e), so
+    // inclusion-exclusion over the block lengths works out to the Möbius function
+    invalid_id_sum(ranges, |len| {
+        divisors(len)
+            .into_iter()
+            .filter(|it| *it < len)
+            .map(|it| (it, -mobius(len / it)))
+            .collect()
+    })
 }
 
-fn invalid_ids(ranges: &[Input], repeats: usize) -> Vec<u64> {
-    let mut n: u64 = 1;
-    let mut ids = vec![];
-    for range in ranges {
-        loop {
-            let double = (n.to_string().repeat(repeats)).parse::<u64>().unwrap();
-            if double > range.max {
-                break;
-            }
-            if double >= range.min {
-                ids.push(double);
+/// Sums the IDs made of a repeated block, where `blocks` gives the block lengths to count for an
+/// ID length together with their weights.
+fn invalid_id_sum(ranges: &IntervalSet<u64>, blocks: impl Fn(u32) -> Vec<(u32, i64)>) -> u64 {
+    let mut total: i128 = 0;
+    for range in ranges.iter() {
+        for len in digit_count(range.min)..=digit_count(range.max) {
+            let lo = range.min.max(pow10(len - 1));
+            // Every u64 has at most 20 digits, so only shorter lengths cap the range
+            let hi = 10u64
+                .checked_pow(len)
+                .map_or(range.max, |it| range.max.min(it - 1));
+            for (block, weight) in blocks(len) {
+                // Within a fixed length, the IDs are exactly the multiples of this factor
+                let factor = block_repunit(block, len / block);
+                let sum = i128::try_from(sum_of_multiples(factor, lo, hi)).unwrap();
+                total += i128::from(weight) * sum;
             }
-            n += 1;
         }
     }
-    ids
-}
-
-#[derive(Parse, ParseFromStr, PartialEq, Eq, Debug, Hash, Clone)]
-#[prse = "{min}-{max}"]
-struct Input {
-    min: u64,
-    max: u64,
+    u64::try_from(total).expect("Sum of invalid IDs does not fit into a u64")
 }
@@ -1,4 +1,5 @@
 use crate::util::AdventHelper;
+use crate::util::transform::Transform;
 use array2d::Array2D;
 use itertools::Itertools;
 
@@ -16,6 +17,7 @@ pub fn main() {
     advent.part2("Result: {}", part2(&rows));
 }
 
+#[allow(clippy::needless_range_loop)]
 fn part1(rows: &[Vec<String>]) -> u64 {
     let mut sum = 0;
     for col in 0..rows.first().unwrap().len() {
@@ -36,7 +38,7 @@ fn part1(rows: &[Vec<String>]) -> u64 {
 fn part2(grid: &Array2D<char>) -> u64 {
     let mut sum = 0;
     let mut numbers = vec![];
-    grid.as_columns().iter().rev().for_each(|it| {
+    grid.rotate_ccw().as_rows().iter().for_each(|it| {
         let raw: String = it.iter().dropping_back(1).collect();
         if let Ok(n) = raw.trim().parse::<u64>() {
             numbers.push(n);
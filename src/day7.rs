@@ -1,32 +1,47 @@
-use crate::util::AdventHelper;
+use crate::util::Dir::{Left, Right};
+use crate::util::animation::Recorder;
+use crate::util::render::{Colour, Frame};
+use crate::util::{AdventHelper, BitSetGrid, Point};
 use array2d::Array2D;
-use bit_set::BitSet;
+use itertools::Itertools;
 
-pub fn main() {
+pub fn main(recorder: &mut Recorder) {
     let advent = AdventHelper::from_file_name(file!());
     let grid = advent.parse_grid_2d();
-    advent.part1("Result: {}", part1(&grid));
+    advent.part1("Result: {}", part1(&grid, recorder));
     advent.part2("Result: {}", part2(&grid));
 }
 
-fn part1(grid: &Array2D<char>) -> usize {
-    let mut beams = BitSet::new();
+fn part1(grid: &Array2D<char>, recorder: &mut Recorder) -> usize {
+    let mut beams = BitSetGrid::new(1, grid.num_columns());
     let mut count = 0;
-    for row in grid.rows_iter() {
-        row.enumerate().for_each(|(i, it)| {
-            if *it == 'S' {
-                beams.insert(i);
-            }
-            if *it == '^' && beams.contains(i) {
-                beams.remove(i);
-                beams.insert(i - 1);
-                beams.insert(i + 1);
-                count += 1
-            }
+    for (y, row) in grid.as_rows().into_iter().enumerate() {
+        beams.union_with(&cells(&row, 'S'));
+        let hit = beams.intersection(&cells(&row, '^'));
+        count += hit.count();
+        beams.difference_with(&hit);
+        beams.union_with(&hit.shift(Left));
+        beams.union_with(&hit.shift(Right));
+        recorder.record(|| {
+            let beams = beams
+                .into_iter()
+                .map(|p| Point::new(p.x, i32::try_from(y).unwrap()));
+            Frame::new(grid).highlight(beams, Colour::Cyan)
         });
     }
     count
 }
+
+fn cells(row: &[char], c: char) -> BitSetGrid {
+    let mut cells = BitSetGrid::new(1, row.len());
+    cells.extend(
+        row.iter()
+            .positions(|it| *it == c)
+            .map(|x| Point::from_index(x, 0)),
+    );
+    cells
+}
+
 fn part2(grid: &Array2D<char>) -> usize {
     let mut beams = vec![0; grid.num_columns()];
     for row in grid.rows_iter() {
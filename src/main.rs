@@ -1,5 +1,3 @@
-#![feature(let_chains)]
-
 mod day1;
 mod day2;
 mod day3;
@@ -19,7 +17,11 @@ extern crate dotenv;
 use std::env;
 use std::time::Instant;
 
-use clap::{Parser, arg};
+use clap::Parser;
+
+#[cfg(feature = "visualize")]
+use crate::util::animation::Output;
+use crate::util::animation::Recorder;
 
 /// Simple program to greet a person
 #[derive(Parser, Debug)]
@@ -36,6 +38,17 @@ struct Args {
     /// Measure execution time
     #[arg(short, long, env, default_value_t = false)]
     time: bool,
+
+    /// Record simulation steps and replay them in the terminal, or write them to a .gif file
+    /// or a directory of PNGs ({} in the path is replaced with the day)
+    #[cfg(feature = "visualize")]
+    #[arg(long, num_args = 0..=1, require_equals = true, default_missing_value = "terminal")]
+    visualize: Option<String>,
+
+    /// Frames per second for --visualize
+    #[cfg(feature = "visualize")]
+    #[arg(long, default_value_t = 10)]
+    fps: u32,
 }
 fn main() {
     let args = Args::parse();
@@ -49,27 +62,28 @@ fn main() {
     if args.day == 0 {
         let start = Instant::now();
         for d in 1..=25 {
-            run(d, args.time);
+            run(d, &args);
             println!();
         }
         if args.time {
             println!("Total Time: {} ms", start.elapsed().as_millis());
         }
     } else {
-        run(args.day, args.time)
+        run(args.day, &args)
     }
 }
 
-fn run(day: i32, time: bool) {
+fn run(day: i32, args: &Args) {
+    let mut recorder = recorder(day, args);
     let start = Instant::now();
     match day {
         1 => day1::main(),
         2 => day2::main(),
         3 => day3::main(),
-        4 => day4::main(),
+        4 => day4::main(&mut recorder),
         5 => day5::main(),
         6 => day6::main(),
-        7 => day7::main(),
+        7 => day7::main(&mut recorder),
         8 => day8::main(),
         9 => day9::main(),
         10 => day10::main(),
@@ -78,7 +92,8 @@ fn run(day: i32, time: bool) {
         }
     }
     let duration = start.elapsed();
-    if time {
+    recorder.finish();
+    if args.time {
         if duration.as_millis() < 3 {
             println!("Time: {} μs", duration.as_micros())
         } else {
@@ -86,3 +101,22 @@ fn run(day: i32, time: bool) {
         }
     }
 }
+
+#[cfg(feature = "visualize")]
+fn recorder(day: i32, args: &Args) -> Recorder {
+    match &args.visualize {
+        Some(target) => Recorder::new(
+            day,
+            Output {
+                target: target.clone(),
+                fps: args.fps,
+            },
+        ),
+        None => Recorder::disabled(),
+    }
+}
+
+#[cfg(not(feature = "visualize"))]
+fn recorder(_day: i32, _args: &Args) -> Recorder {
+    Recorder::disabled()
+}
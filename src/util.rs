@@ -1,16 +1,43 @@
 use itertools::{Itertools, Product};
 
 use crate::util::Dir::{Down, Left, Right, Up};
+use crate::util::automaton::Neighbourhood;
+use crate::util::render::Frame;
 use array2d::Array2D;
 use bit_set::{BitSet, Iter};
 use itertools::traits::HomogeneousTuple;
+use num::{PrimInt, Signed};
+use prse::{Parse, try_parse};
+use std::borrow::{Borrow, Cow};
 use std::collections::HashSet;
 use std::fmt::{Debug, Display, Formatter};
+use std::hash::Hash;
 use std::iter::Map;
-use std::ops::RangeInclusive;
+use std::ops::{Add, AddAssign, Mul, Neg, RangeInclusive, Sub, SubAssign};
 use std::str::FromStr;
 use std::{env, fs};
 
+pub mod animation;
+pub mod automaton;
+pub mod compress;
+pub mod gf2;
+pub mod graph;
+pub mod hex;
+pub mod ilp;
+pub mod interval;
+pub mod kdtree;
+pub mod matrix;
+pub mod numtheory;
+pub mod pathfind;
+pub mod point3;
+pub mod polygon;
+pub mod prefix_sum;
+pub mod region;
+pub mod render;
+pub mod sparse;
+pub mod transform;
+pub mod union_find;
+
 pub fn parse_from_strings<T: FromStr>(file_path: &str) -> Vec<T>
 where
     <T as FromStr>::Err: Debug,
@@ -176,9 +203,9 @@ impl AdventHelper {
         let mut vec = self.parse_grid();
         let length = vec.iter().map(|row| row.len()).max().unwrap();
         vec.iter_mut()
-            .for_each(|row| row.extend(std::iter::repeat(fill).take(length - row.len())));
+            .for_each(|row| row.extend(std::iter::repeat_n(fill, length - row.len())));
         for row in vec.iter_mut() {
-            row.extend(std::iter::repeat(fill).take(length - row.len()));
+            row.extend(std::iter::repeat_n(fill, length - row.len()));
         }
         Array2D::from_rows(&vec).unwrap()
     }
@@ -206,6 +233,11 @@ impl AdventHelper {
 
 pub trait GridAccess<T> {
     fn get_i32(&self, x: i32, y: i32) -> Option<&T>;
+
+    /// Looks up `p` with `x` as the column and `y` as the row.
+    fn get_point(&self, p: &Point) -> Option<&T> {
+        self.get_i32(p.y, p.x)
+    }
 }
 impl<T> GridAccess<T> for Array2D<T> {
     fn get_i32(&self, x: i32, y: i32) -> Option<&T> {
@@ -216,71 +248,123 @@ impl<T> GridAccess<T> for Array2D<T> {
     }
 }
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, Ord, PartialOrd)]
-pub struct Point {
-    pub(crate) x: i32,
-    pub(crate) y: i32,
+/// Signed integer type usable as a `Point` coordinate.
+pub trait Coord: PrimInt + Signed + Hash + Debug + Display {}
+
+impl<T: PrimInt + Signed + Hash + Debug + Display> Coord for T {}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, Ord, PartialOrd, Default)]
+pub struct Point<T = i32> {
+    pub(crate) x: T,
+    pub(crate) y: T,
 }
 
-impl Display for Point {
+/// For puzzles whose coordinates don't fit into an `i32`.
+pub type Point64 = Point<i64>;
+
+impl<T: Display> Display for Point<T> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(f, "<{}, {}>", self.x, self.y)
     }
 }
 
-pub trait IntoUnsafe<T>: Sized {
-    fn into_unsafe(self) -> T;
+impl<T: for<'a> Parse<'a>> FromStr for Point<T> {
+    type Err = prse::ParseError;
+
+    /// Parses `x,y`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (x, y) = try_parse!(s, "{},{}")?;
+        Ok(Point { x, y })
+    }
 }
 
-impl IntoUnsafe<i32> for usize {
-    fn into_unsafe(self) -> i32 {
-        self as i32
+impl<T: Coord> Add for Point<T> {
+    type Output = Point<T>;
+
+    fn add(self, rhs: Point<T>) -> Point<T> {
+        Point::new(self.x + rhs.x, self.y + rhs.y)
     }
 }
 
-impl<T> IntoUnsafe<T> for T {
-    fn into_unsafe(self) -> T {
-        self
+impl<T: Coord> Sub for Point<T> {
+    type Output = Point<T>;
+
+    fn sub(self, rhs: Point<T>) -> Point<T> {
+        Point::new(self.x - rhs.x, self.y - rhs.y)
     }
 }
 
-impl Point {
-    pub(crate) fn new(x: impl IntoUnsafe<i32>, y: impl IntoUnsafe<i32>) -> Point {
-        Point {
-            x: x.into_unsafe(),
-            y: y.into_unsafe(),
-        }
+impl<T: Coord> Mul<T> for Point<T> {
+    type Output = Point<T>;
+
+    fn mul(self, rhs: T) -> Point<T> {
+        Point::new(self.x * rhs, self.y * rhs)
     }
+}
 
-    pub fn up(&self) -> Point {
-        Point {
-            x: self.x,
-            y: self.y - 1,
-        }
+impl<T: Coord> Neg for Point<T> {
+    type Output = Point<T>;
+
+    fn neg(self) -> Point<T> {
+        Point::new(-self.x, -self.y)
     }
+}
 
-    pub fn down(&self) -> Point {
-        Point {
-            x: self.x,
-            y: self.y + 1,
-        }
+impl<T: Coord> AddAssign for Point<T> {
+    fn add_assign(&mut self, rhs: Point<T>) {
+        *self = *self + rhs;
     }
+}
 
-    pub fn left(&self) -> Point {
-        Point {
-            x: self.x - 1,
-            y: self.y,
-        }
+impl<T: Coord> SubAssign for Point<T> {
+    fn sub_assign(&mut self, rhs: Point<T>) {
+        *self = *self - rhs;
     }
+}
 
-    pub fn right(&self) -> Point {
-        Point {
-            x: self.x + 1,
-            y: self.y,
-        }
+impl<T: Coord> Point<T> {
+    pub fn new(x: T, y: T) -> Point<T> {
+        Point { x, y }
     }
 
-    pub fn mv(&self, d: Dir) -> Point {
+    /// Point for a column and row index, or `None` if they don't fit into `T`.
+    pub fn try_from_index(col: usize, row: usize) -> Option<Point<T>> {
+        Some(Point::new(T::from(col)?, T::from(row)?))
+    }
+
+    /// Point for a column and row index, panicking if they don't fit into `T`.
+    pub fn from_index(col: usize, row: usize) -> Point<T> {
+        Point::try_from_index(col, row)
+            .unwrap_or_else(|| panic!("Index ({}, {}) does not fit into a Point", col, row))
+    }
+
+    /// Column and row index, or `None` for negative coordinates.
+    pub fn to_index(self) -> Option<(usize, usize)> {
+        Some((self.x.to_usize()?, self.y.to_usize()?))
+    }
+
+    /// Converts to another coordinate type, or `None` if a coordinate doesn't fit.
+    pub fn cast<U: Coord>(&self) -> Option<Point<U>> {
+        Some(Point::new(U::from(self.x)?, U::from(self.y)?))
+    }
+
+    pub fn up(&self) -> Point<T> {
+        Point::new(self.x, self.y - T::one())
+    }
+
+    pub fn down(&self) -> Point<T> {
+        Point::new(self.x, self.y + T::one())
+    }
+
+    pub fn left(&self) -> Point<T> {
+        Point::new(self.x - T::one(), self.y)
+    }
+
+    pub fn right(&self) -> Point<T> {
+        Point::new(self.x + T::one(), self.y)
+    }
+
+    pub fn mv(&self, d: Dir) -> Point<T> {
         match d {
             Up => self.up(),
             Right => self.right(),
@@ -289,8 +373,8 @@ impl Point {
         }
     }
 
-    pub fn mv_mulitple(&self, d: Dir, n: usize) -> Point {
-        let n = n as i32;
+    pub fn mv_mulitple(&self, d: Dir, n: usize) -> Point<T> {
+        let n = T::from(n).expect("Distance does not fit into a coordinate");
         match d {
             Up => Point::new(self.x, self.y - n),
             Right => Point::new(self.x + n, self.y),
@@ -299,29 +383,29 @@ impl Point {
         }
     }
 
-    pub fn neighbours(&self) -> Vec<Point> {
+    pub fn manhattan(&self, other: &Point<T>) -> T {
+        (self.x - other.x).abs() + (self.y - other.y).abs()
+    }
+
+    pub fn neighbours(&self) -> Vec<Point<T>> {
         vec![self.up(), self.down(), self.left(), self.right()]
     }
+}
 
+impl Point {
     pub fn in_bounds(&self, b: &Bounds) -> bool {
         b.contains(self)
     }
 
-    pub fn bounds<H>(col: &HashSet<Point, H>) -> Bounds {
-        let min_x = col.iter().map(|p| p.x).min().unwrap();
-        let max_x = col.iter().map(|p| p.x).max().unwrap();
-        let min_y = col.iter().map(|p| p.y).min().unwrap();
-        let max_y = col.iter().map(|p| p.y).max().unwrap();
-        Bounds {
-            min_x,
-            max_x,
-            min_y,
-            max_y,
-        }
+    pub fn bounds<H>(col: &HashSet<Point, H>) -> Option<Bounds> {
+        Bounds::from_points(col)
     }
 }
 
+/// Set of points backed by a `BitSet` covering `bounds()`, growing when inserting outside of it.
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct BitSetGrid {
+    origin: Point,
     height: usize,
     width: usize,
     inner: BitSet,
@@ -333,6 +417,7 @@ impl<'a> IntoIterator for &'a BitSetGrid {
 
     fn into_iter(self) -> Self::IntoIter {
         BitSetGridIter {
+            origin: self.origin,
             width: self.width,
             inner: self.inner.into_iter(),
         }
@@ -340,6 +425,7 @@ impl<'a> IntoIterator for &'a BitSetGrid {
 }
 
 pub struct BitSetGridIter<'a> {
+    origin: Point,
     width: usize,
     inner: Iter<'a, u32>,
 }
@@ -349,53 +435,314 @@ impl Iterator for BitSetGridIter<'_> {
 
     fn next(&mut self) -> Option<Self::Item> {
         self.inner.next().map(|v| Point {
-            x: (v % self.width) as i32,
-            y: (v / self.width) as i32,
+            x: self.origin.x + (v % self.width) as i32,
+            y: self.origin.y + (v / self.width) as i32,
         })
     }
 }
 
+impl Display for BitSetGrid {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", Frame::new(self))
+    }
+}
+
+impl FromIterator<Point> for BitSetGrid {
+    fn from_iter<I: IntoIterator<Item = Point>>(iter: I) -> Self {
+        let points = iter.into_iter().collect_vec();
+        let mut grid = match Bounds::from_points(&points) {
+            Some(bounds) => BitSetGrid::with_bounds(bounds),
+            None => BitSetGrid::new(0, 0),
+        };
+        grid.extend(points);
+        grid
+    }
+}
+
+impl Extend<Point> for BitSetGrid {
+    fn extend<I: IntoIterator<Item = Point>>(&mut self, iter: I) {
+        for p in iter {
+            self.insert(&p);
+        }
+    }
+}
+
 impl BitSetGrid {
     pub fn new(height: usize, width: usize) -> BitSetGrid {
         BitSetGrid {
+            origin: Point::new(0, 0),
             height,
             width,
             inner: BitSet::with_capacity(width * height),
         }
     }
 
+    pub fn with_bounds(bounds: Bounds) -> BitSetGrid {
+        BitSetGrid {
+            origin: Point::new(bounds.min_x, bounds.min_y),
+            ..BitSetGrid::new(bounds.height() as usize, bounds.width() as usize)
+        }
+    }
+
     pub fn from_hashset<H>(col: &HashSet<Point, H>) -> BitSetGrid {
-        let bounds = Point::bounds(col);
-        let mut grid = BitSetGrid::new(bounds.max_y as usize + 1, bounds.max_x as usize + 1);
-        for p in col {
-            grid.insert(p);
+        col.iter().copied().collect()
+    }
+
+    pub fn to_hashset(&self) -> HashSet<Point> {
+        self.into_iter().collect()
+    }
+
+    fn index(&self, point: &Point) -> Option<usize> {
+        let x = usize::try_from(point.x - self.origin.x).ok()?;
+        let y = usize::try_from(point.y - self.origin.y).ok()?;
+        if x < self.width && y < self.height {
+            Some(y * self.width + x)
+        } else {
+            None
         }
-        grid
     }
 
+    /// Inserts `point`, growing the grid first if it lies outside of `bounds()`.
     pub fn insert(&mut self, point: &Point) -> bool {
-        self.inner
-            .insert((point.y as usize) * self.width + point.x as usize)
+        if self.index(point).is_none() {
+            self.grow_to_include(point);
+        }
+        let idx = self.index(point).unwrap();
+        self.inner.insert(idx)
     }
 
     pub fn remove(&mut self, point: &Point) -> bool {
-        self.inner
-            .remove((point.y as usize) * self.width + point.x as usize)
+        match self.index(point) {
+            Some(idx) => self.inner.remove(idx),
+            None => false,
+        }
     }
 
     pub fn contains(&self, point: &Point) -> bool {
-        self.inner
-            .contains((point.y as usize) * self.width + point.x as usize)
+        match self.index(point) {
+            Some(idx) => self.inner.contains(idx),
+            None => false,
+        }
     }
 
-    pub fn bounds(&self) -> Bounds {
-        Bounds {
-            min_x: 0,
-            max_x: (self.width - 1) as i32,
-            min_y: 0,
-            max_y: (self.height - 1) as i32,
+    /// The area the grid covers, `None` while it covers nothing.
+    pub fn bounds(&self) -> Option<Bounds> {
+        (self.width > 0 && self.height > 0).then(|| Bounds {
+            min_x: self.origin.x,
+            max_x: self.origin.x + self.width as i32 - 1,
+            min_y: self.origin.y,
+            max_y: self.origin.y + self.height as i32 - 1,
+        })
+    }
+
+    /// Moves the grid to `bounds`, dropping all points outside of it.
+    pub fn resize(&mut self, bounds: Bounds) {
+        let mut resized = BitSetGrid::with_bounds(bounds);
+        for p in self.into_iter().filter(|p| bounds.contains(p)) {
+            let idx = resized.index(&p).unwrap();
+            resized.inner.insert(idx);
+        }
+        *self = resized;
+    }
+
+    fn grow_to_include(&mut self, point: &Point) {
+        let Some(current) = self.bounds() else {
+            self.origin = *point;
+            self.width = 1;
+            self.height = 1;
+            return;
+        };
+        // Grow by at least the current size in each direction that needs it,
+        // so that inserting along a line stays amortised linear.
+        let mut grown = current;
+        if point.x < current.min_x {
+            grown.min_x = point.x.min(current.min_x - current.width());
+        }
+        if point.x > current.max_x {
+            grown.max_x = point.x.max(current.max_x + current.width());
+        }
+        if point.y < current.min_y {
+            grown.min_y = point.y.min(current.min_y - current.height());
+        }
+        if point.y > current.max_y {
+            grown.max_y = point.y.max(current.max_y + current.height());
+        }
+        self.resize(grown);
+    }
+
+    pub fn count(&self) -> usize {
+        self.inner.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    /// `other` moved onto this grid's bounds, only copying when they differ.
+    fn aligned<'a>(&self, other: &'a BitSetGrid) -> Cow<'a, BitSetGrid> {
+        if self.origin == other.origin && self.width == other.width && self.height == other.height {
+            Cow::Borrowed(other)
+        } else if let Some(bounds) = self.bounds() {
+            let mut other = other.clone();
+            other.resize(bounds);
+            Cow::Owned(other)
+        } else {
+            Cow::Owned(BitSetGrid {
+                inner: BitSet::new(),
+                ..*self
+            })
+        }
+    }
+
+    /// Adds all points of `other`, growing to the union of both bounds if necessary.
+    pub fn union_with(&mut self, other: &BitSetGrid) {
+        if let Some(other_bounds) = other.bounds().filter(|_| !other.is_empty()) {
+            match self.bounds() {
+                None => self.resize(other_bounds),
+                Some(b) if !b.contains_bounds(&other_bounds) => self.resize(b.union(&other_bounds)),
+                Some(_) => {}
+            }
+        }
+        self.inner.union_with(&self.aligned(other).inner);
+    }
+
+    pub fn intersect_with(&mut self, other: &BitSetGrid) {
+        self.inner.intersect_with(&self.aligned(other).inner);
+    }
+
+    pub fn difference_with(&mut self, other: &BitSetGrid) {
+        self.inner.difference_with(&self.aligned(other).inner);
+    }
+
+    pub fn union(&self, other: &BitSetGrid) -> BitSetGrid {
+        let mut result = self.clone();
+        result.union_with(other);
+        result
+    }
+
+    pub fn intersection(&self, other: &BitSetGrid) -> BitSetGrid {
+        let mut result = self.clone();
+        result.intersect_with(other);
+        result
+    }
+
+    pub fn difference(&self, other: &BitSetGrid) -> BitSetGrid {
+        let mut result = self.clone();
+        result.difference_with(other);
+        result
+    }
+
+    /// Moves every point one cell in direction `d`; points leaving `bounds()` are dropped.
+    pub fn shift(&self, d: Dir) -> BitSetGrid {
+        match d {
+            Up => self.shift_by(0, -1),
+            Right => self.shift_by(1, 0),
+            Down => self.shift_by(0, 1),
+            Left => self.shift_by(-1, 0),
+        }
+    }
+
+    /// Moves every point by `(dx, dy)`; points leaving `bounds()` are dropped.
+    ///
+    /// Works on whole words of the underlying `BitSet` rather than on single cells.
+    pub fn shift_by(&self, dx: i32, dy: i32) -> BitSetGrid {
+        let nbits = self.width * self.height;
+        let words = self.inner.get_ref().storage();
+        let mut shifted = shift_words(words, dy as isize * self.width as isize + dx as isize);
+        if dx != 0 {
+            // Cells that wrapped around into a neighbouring row
+            let (lo, hi) = if dx > 0 {
+                (0, (dx as usize).min(self.width))
+            } else {
+                (
+                    self.width.saturating_sub(dx.unsigned_abs() as usize),
+                    self.width,
+                )
+            };
+            for row in 0..self.height {
+                clear_bits(&mut shifted, row * self.width + lo, row * self.width + hi);
+            }
+        }
+        clear_bits(&mut shifted, nbits, usize::MAX);
+
+        // Same length as the original storage, every bit taken from the shifted words
+        let mut bits = self.inner.get_ref().clone();
+        let word_bits = u32::BITS as usize;
+        for i in 0..bits.len() {
+            bits.set(i, shifted[i / word_bits] >> (i % word_bits) & 1 == 1);
+        }
+        BitSetGrid {
+            inner: BitSet::from_bit_vec(bits),
+            ..*self
+        }
+    }
+
+    /// All set cells together with their neighbours, clipped to `bounds()`.
+    ///
+    /// Use `resize(bounds().expand(1))` first to keep cells spilling over the edge.
+    pub fn dilate(&self, neighbourhood: &Neighbourhood) -> BitSetGrid {
+        match neighbourhood {
+            Neighbourhood::Eight => {
+                let mut row = self.union(&self.shift(Left));
+                row.union_with(&self.shift(Right));
+                let mut result = row.union(&row.shift(Up));
+                result.union_with(&row.shift(Down));
+                result
+            }
+            other => {
+                let mut result = self.clone();
+                for (dx, dy) in other.offsets() {
+                    result.union_with(&self.shift_by(dx, dy));
+                }
+                result
+            }
+        }
+    }
+}
+
+/// Shifts a little-endian bit vector towards higher indices by `k` bits (lower for negative `k`).
+fn shift_words(words: &[u32], k: isize) -> Vec<u32> {
+    let n = words.len();
+    let (w, b) = (k.unsigned_abs() / 32, (k.unsigned_abs() % 32) as u32);
+    let mut out = vec![0; n];
+    for (i, word) in out.iter_mut().enumerate() {
+        if k >= 0 {
+            if i < w {
+                continue;
+            }
+            let src = i - w;
+            *word = words[src] << b;
+            if b > 0 && src > 0 {
+                *word |= words[src - 1] >> (32 - b);
+            }
+        } else {
+            let src = i + w;
+            if src >= n {
+                break;
+            }
+            *word = words[src] >> b;
+            if b > 0 && src + 1 < n {
+                *word |= words[src + 1] << (32 - b);
+            }
         }
     }
+    out
+}
+
+fn clear_bits(words: &mut [u32], start: usize, end: usize) {
+    let end = end.min(words.len() * 32);
+    let mut i = start;
+    while i < end {
+        let bit = i % 32;
+        let len = (32 - bit).min(end - i);
+        let mask = if len == 32 {
+            u32::MAX
+        } else {
+            ((1 << len) - 1) << bit
+        };
+        words[i / 32] &= !mask;
+        i += len;
+    }
 }
 
 #[derive(Clone, Eq, PartialEq, Debug, Hash, Copy)]
@@ -435,7 +782,7 @@ impl Dir {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
 pub struct Bounds {
     pub min_x: i32,
     pub max_x: i32,
@@ -443,6 +790,16 @@ pub struct Bounds {
     pub max_y: i32,
 }
 
+impl Display for Bounds {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "<{}..={}, {}..={}>",
+            self.min_x, self.max_x, self.min_y, self.max_y
+        )
+    }
+}
+
 impl IntoIterator for Bounds {
     type Item = Point;
     type IntoIter = Map<Product<RangeInclusive<i32>, RangeInclusive<i32>>, fn((i32, i32)) -> Point>;
@@ -463,20 +820,141 @@ impl Bounds {
         self.min_y..=self.max_y
     }
 
+    /// Smallest bounds containing all points, or `None` if there are none.
+    pub fn from_points<I>(points: I) -> Option<Bounds>
+    where
+        I: IntoIterator,
+        I::Item: Borrow<Point>,
+    {
+        points.into_iter().fold(None, |acc, p| {
+            let p = p.borrow();
+            Some(match acc {
+                None => Bounds {
+                    min_x: p.x,
+                    max_x: p.x,
+                    min_y: p.y,
+                    max_y: p.y,
+                },
+                Some(b) => Bounds {
+                    min_x: b.min_x.min(p.x),
+                    max_x: b.max_x.max(p.x),
+                    min_y: b.min_y.min(p.y),
+                    max_y: b.max_y.max(p.y),
+                },
+            })
+        })
+    }
+
+    pub fn width(&self) -> i32 {
+        self.max_x - self.min_x + 1
+    }
+
+    pub fn height(&self) -> i32 {
+        self.max_y - self.min_y + 1
+    }
+
+    pub fn area(&self) -> i64 {
+        self.width() as i64 * self.height() as i64
+    }
+
     pub fn contains(&self, p: &Point) -> bool {
         self.min_x <= p.x && p.x <= self.max_x && self.min_y <= p.y && p.y <= self.max_y
     }
 
-    pub fn expand(&self, _n: i32) -> Bounds {
+    pub fn contains_bounds(&self, other: &Bounds) -> bool {
+        self.min_x <= other.min_x
+            && other.max_x <= self.max_x
+            && self.min_y <= other.min_y
+            && other.max_y <= self.max_y
+    }
+
+    /// Moves `p` to the closest point inside the bounds.
+    pub fn clamp(&self, p: &Point) -> Point {
+        Point {
+            x: p.x.clamp(self.min_x, self.max_x),
+            y: p.y.clamp(self.min_y, self.max_y),
+        }
+    }
+
+    /// Grows the bounds by `n` in every direction (shrinks for negative `n`).
+    pub fn expand(&self, n: i32) -> Bounds {
+        Bounds {
+            min_x: self.min_x - n,
+            max_x: self.max_x + n,
+            min_y: self.min_y - n,
+            max_y: self.max_y + n,
+        }
+    }
+
+    pub fn intersect(&self, other: &Bounds) -> Option<Bounds> {
+        let b = Bounds {
+            min_x: self.min_x.max(other.min_x),
+            max_x: self.max_x.min(other.max_x),
+            min_y: self.min_y.max(other.min_y),
+            max_y: self.max_y.min(other.max_y),
+        };
+        b.non_empty()
+    }
+
+    /// Smallest bounds containing both.
+    pub fn union(&self, other: &Bounds) -> Bounds {
         Bounds {
-            min_x: self.min_x - 1,
-            max_x: self.max_x + 1,
-            min_y: self.min_y - 1,
-            max_y: self.max_y + 1,
+            min_x: self.min_x.min(other.min_x),
+            max_x: self.max_x.max(other.max_x),
+            min_y: self.min_y.min(other.min_y),
+            max_y: self.max_y.max(other.max_y),
+        }
+    }
+
+    /// Splits into the columns left of `x` and the columns from `x` on.
+    pub fn split_x(&self, x: i32) -> (Option<Bounds>, Option<Bounds>) {
+        let left = Bounds {
+            max_x: (x - 1).min(self.max_x),
+            ..*self
+        };
+        let right = Bounds {
+            min_x: x.max(self.min_x),
+            ..*self
+        };
+        (left.non_empty(), right.non_empty())
+    }
+
+    /// Splits into the rows above `y` and the rows from `y` on.
+    pub fn split_y(&self, y: i32) -> (Option<Bounds>, Option<Bounds>) {
+        let top = Bounds {
+            max_y: (y - 1).min(self.max_y),
+            ..*self
+        };
+        let bottom = Bounds {
+            min_y: y.max(self.min_y),
+            ..*self
+        };
+        (top.non_empty(), bottom.non_empty())
+    }
+
+    /// Splits into up to four quadrants, with `p` being the top left corner of the last one.
+    pub fn split(&self, p: &Point) -> Vec<Bounds> {
+        let (top, bottom) = self.split_y(p.y);
+        [top, bottom]
+            .into_iter()
+            .flatten()
+            .flat_map(|half| {
+                let (left, right) = half.split_x(p.x);
+                [left, right]
+            })
+            .flatten()
+            .collect()
+    }
+
+    fn non_empty(self) -> Option<Bounds> {
+        if self.min_x <= self.max_x && self.min_y <= self.max_y {
+            Some(self)
+        } else {
+            None
         }
     }
 
-    pub fn to_set(&self) -> HashSet<Point> {
+    pub fn to_set(self) -> HashSet<Point> {
         let mut set = HashSet::new();
         for x in self.min_x..=self.max_x {
             for y in self.min_y..=self.max_y {
@@ -494,7 +972,10 @@ pub enum Part {
 
 #[cfg(test)]
 mod tests {
-    use crate::util::parse_strings;
+    use crate::util::Dir::{Down, Left, Right, Up};
+    use crate::util::automaton::Neighbourhood;
+    use crate::util::{BitSetGrid, Bounds, Point, Point64, parse_strings};
+    use std::collections::HashSet;
     use std::io::Write;
     use tempfile::NamedTempFile;
 
@@ -511,4 +992,177 @@ mod tests {
             vec!["This".to_string(), "is a".to_string(), "File!".to_string()];
         assert_eq!(strings, expected_strings);
     }
+
+    #[test]
+    fn expands_by_n() {
+        let b = Bounds {
+            min_x: 0,
+            max_x: 2,
+            min_y: 1,
+            max_y: 1,
+        };
+
+        assert_eq!(b.expand(2).area(), 7 * 5);
+        assert_eq!(b.expand(0), b);
+        assert_eq!(b.expand(2).expand(-2), b);
+    }
+
+    #[test]
+    fn combines_bounds() {
+        let a = Bounds::from_points([Point::new(0, 0), Point::new(4, 4)]).unwrap();
+        let b = Bounds::from_points([Point::new(3, 2), Point::new(6, 9)].iter()).unwrap();
+
+        let intersection = a.intersect(&b).unwrap();
+        assert_eq!(
+            Bounds::from_points([Point::new(3, 2), Point::new(4, 4)]),
+            Some(intersection)
+        );
+        assert_eq!(
+            a.union(&b),
+            Bounds::from_points([Point::new(0, 0), Point::new(6, 9)]).unwrap()
+        );
+        assert!(a.union(&b).contains_bounds(&a));
+        assert!(!a.contains_bounds(&b));
+        assert_eq!(
+            a.intersect(&a.expand(-1).split_x(2).1.unwrap().expand(5)),
+            Some(a)
+        );
+        assert_eq!(a.clamp(&Point::new(-3, 7)), Point::new(0, 4));
+        assert_eq!(Point::bounds(&HashSet::<Point>::new()), None);
+    }
+
+    #[test]
+    fn splits_into_quadrants() {
+        let b = Bounds::from_points([Point::new(0, 0), Point::new(4, 3)]).unwrap();
+
+        let quadrants = b.split(&Point::new(2, 1));
+        assert_eq!(quadrants.len(), 4);
+        assert_eq!(quadrants.iter().map(|it| it.area()).sum::<i64>(), b.area());
+        assert_eq!(
+            quadrants[3],
+            Bounds::from_points([Point::new(2, 1), Point::new(4, 3)]).unwrap()
+        );
+        assert_eq!(b.split(&Point::new(0, 0)), vec![b]);
+    }
+
+    #[test]
+    fn bit_set_grid_handles_negative_coordinates() {
+        let points: HashSet<Point> = [Point::new(-3, 2), Point::new(5, -1), Point::new(0, 0)]
+            .into_iter()
+            .collect();
+        let mut grid = BitSetGrid::from_hashset(&points);
+
+        assert_eq!(grid.bounds(), Point::bounds(&points));
+        assert!(grid.contains(&Point::new(-3, 2)));
+        assert!(!grid.contains(&Point::new(-3, 1)));
+        assert!(!grid.contains(&Point::new(-30, 100)));
+        assert!(!grid.remove(&Point::new(-30, 100)));
+        assert_eq!(grid.to_hashset(), points);
+
+        assert!(grid.insert(&Point::new(-30, 100)));
+        assert!(!grid.insert(&Point::new(-30, 100)));
+        assert!(grid.bounds().unwrap().contains(&Point::new(-30, 100)));
+        assert_eq!(grid.into_iter().count(), 4);
+        assert!(grid.contains(&Point::new(5, -1)));
+
+        let mut empty = BitSetGrid::new(0, 0);
+        assert_eq!(empty.bounds(), None);
+        empty.union_with(&grid);
+        assert_eq!(empty.to_hashset(), grid.to_hashset());
+    }
+
+    #[test]
+    fn bit_set_grid_grows_from_empty() {
+        let mut grid = BitSetGrid::new(0, 0);
+        for x in 0..100 {
+            grid.insert(&Point::new(-x, x / 2));
+        }
+
+        assert_eq!(grid.into_iter().count(), 100);
+        assert!(grid.contains(&Point::new(-99, 49)));
+        assert!(!grid.contains(&Point::new(-99, 48)));
+    }
+
+    #[test]
+    fn bit_set_grid_set_algebra() {
+        let a: BitSetGrid = [Point::new(0, 0), Point::new(1, 0), Point::new(2, 1)]
+            .into_iter()
+            .collect();
+        let b: BitSetGrid = [Point::new(1, 0), Point::new(-5, 3)].into_iter().collect();
+
+        assert_eq!(a.union(&b).count(), 4);
+        assert_eq!(
+            a.intersection(&b).to_hashset(),
+            HashSet::from([Point::new(1, 0)])
+        );
+        assert_eq!(a.difference(&b).count(), 2);
+        assert!(!a.difference(&b).contains(&Point::new(1, 0)));
+        assert_eq!(b.union(&a).to_hashset(), a.union(&b).to_hashset());
+    }
+
+    #[test]
+    fn bit_set_grid_shifts_match_per_cell_moves() {
+        let points: HashSet<Point> = (0..40)
+            .map(|i| Point::new(i * 7 % 37, i * 11 % 23))
+            .collect();
+        let mut grid = BitSetGrid::from_hashset(&points);
+        grid.resize(grid.bounds().unwrap().expand(1));
+        let bounds = grid.bounds().unwrap();
+
+        for d in [Up, Right, Down, Left] {
+            let expected: HashSet<Point> = points
+                .iter()
+                .map(|p| p.mv(d))
+                .filter(|p| bounds.contains(p))
+                .collect();
+            assert_eq!(grid.shift(d).to_hashset(), expected);
+        }
+        let expected: HashSet<Point> = points
+            .iter()
+            .map(|p| Point::new(p.x - 33, p.y + 2))
+            .filter(|p| bounds.contains(p))
+            .collect();
+        assert_eq!(grid.shift_by(-33, 2).to_hashset(), expected);
+
+        for neighbourhood in [Neighbourhood::Four, Neighbourhood::Eight] {
+            let expected: HashSet<Point> = points
+                .iter()
+                .flat_map(|p| {
+                    neighbourhood
+                        .offsets()
+                        .into_iter()
+                        .map(|(dx, dy)| Point::new(p.x + dx, p.y + dy))
+                        .chain([*p])
+                })
+                .collect();
+            assert_eq!(grid.dilate(&neighbourhood).to_hashset(), expected);
+        }
+    }
+
+    #[test]
+    fn points_work_with_any_signed_coordinate() {
+        let big: Point64 = "3000000000,-2".parse().unwrap();
+
+        assert_eq!(big, Point64::new(3_000_000_000, -2));
+        assert_eq!(big.mv_mulitple(Left, 5).manhattan(&big), 5);
+        assert_eq!(big + Point64::new(1, 1) - big, Point64::new(1, 1));
+        assert_eq!(-(big * 2), Point64::new(-6_000_000_000, 4));
+        assert_eq!(big.cast::<i32>(), None);
+        assert_eq!(Point64::new(3, -2).cast(), Some(Point::new(3, -2)));
+        assert!("1,x".parse::<Point>().is_err());
+    }
+
+    #[test]
+    fn converts_indices_checked() {
+        let p: Point = Point::from_index(4, 7);
+
+        assert_eq!(p, Point::new(4, 7));
+        assert_eq!(p.to_index(), Some((4, 7)));
+        assert_eq!(p.left().mv_mulitple(Left, 4).to_index(), None);
+        assert_eq!(Point::<i32>::try_from_index(usize::MAX, 0), None);
+        let mut q = p;
+        q += Point::new(1, 1);
+        q -= Point::new(0, 2);
+        assert_eq!(q, Point::new(5, 6));
+    }
 }
@@ -0,0 +1,226 @@
+use crate::util::render::Frame;
+#[cfg(feature = "visualize")]
+use crate::util::render::{Colour, encode_gif};
+#[cfg(feature = "visualize")]
+use std::io::Write;
+#[cfg(feature = "visualize")]
+use std::path::Path;
+#[cfg(feature = "visualize")]
+use std::time::Duration;
+#[cfg(feature = "visualize")]
+use std::{fs, io, thread};
+
+/// Where `--visualize` sends the recorded frames.
+///
+/// `terminal` replays them, a `.gif` path writes an animation and anything else is used as a
+/// directory for a PNG sequence. A `{}` in the path is replaced with the day.
+#[cfg(feature = "visualize")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Output {
+    pub target: String,
+    pub fps: u32,
+}
+
+/// Collects frames of a simulation when the runner was started with `--visualize`.
+///
+/// Without the `visualize` feature the recorder holds nothing, and `record` compiles to nothing
+/// so its frame closure is never built.
+#[derive(Default)]
+pub struct Recorder {
+    #[cfg(feature = "visualize")]
+    output: Option<Output>,
+    #[cfg(feature = "visualize")]
+    frames: Vec<Frame>,
+}
+
+impl Recorder {
+    /// Recorder that drops every frame.
+    pub fn disabled() -> Recorder {
+        Recorder::default()
+    }
+
+    #[cfg(feature = "visualize")]
+    pub fn new(day: i32, output: Output) -> Recorder {
+        Recorder {
+            output: Some(Output {
+                target: output.target.replace("{}", &day.to_string()),
+                ..output
+            }),
+            frames: vec![],
+        }
+    }
+
+    #[cfg(feature = "visualize")]
+    #[inline]
+    pub fn record(&mut self, frame: impl FnOnce() -> Frame) {
+        if self.output.is_some() {
+            self.frames.push(frame());
+        }
+    }
+
+    #[cfg(not(feature = "visualize"))]
+    #[inline]
+    pub fn record(&mut self, _frame: impl FnOnce() -> Frame) {}
+
+    #[cfg(feature = "visualize")]
+    pub fn frames(&self) -> &[Frame] {
+        &self.frames
+    }
+
+    /// Plays the frames in the terminal, redrawing in place.
+    #[cfg(feature = "visualize")]
+    pub fn replay(&self, fps: u32) -> io::Result<()> {
+        self.replay_to(&mut io::stdout(), fps)
+    }
+
+    #[cfg(feature = "visualize")]
+    fn replay_to(&self, out: &mut impl Write, fps: u32) -> io::Result<()> {
+        let delay = Duration::from_secs_f64(1.0 / fps.max(1) as f64);
+        for (i, frame) in self.frames.iter().enumerate() {
+            write!(out, "\x1b[2J\x1b[H")?;
+            writeln!(out, "{}", frame.clone().ansi(true))?;
+            writeln!(out, "Frame {}/{}", i + 1, self.frames.len())?;
+            out.flush()?;
+            thread::sleep(delay);
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "visualize")]
+    pub fn write_gif(&self, path: impl AsRef<Path>, fps: u32) -> io::Result<()> {
+        let images = self
+            .frames
+            .iter()
+            .map(|it| it.to_image(scale(it), Colour::for_glyph))
+            .collect::<Vec<_>>();
+        if let Some(parent) = path.as_ref().parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, encode_gif(&images, (100 / fps.max(1)) as u16)?)
+    }
+
+    /// Writes `frame_00000.png`, `frame_00001.png`, ... into `dir`.
+    #[cfg(feature = "visualize")]
+    pub fn write_png_sequence(&self, dir: impl AsRef<Path>) -> io::Result<()> {
+        fs::create_dir_all(&dir)?;
+        for (i, frame) in self.frames.iter().enumerate() {
+            frame
+                .to_image(scale(frame), Colour::for_glyph)
+                .write_png(dir.as_ref().join(format!("frame_{:05}.png", i)))?;
+        }
+        Ok(())
+    }
+
+    /// Sends the frames to the `Output` the recorder was created with.
+    pub fn finish(&self) {
+        #[cfg(feature = "visualize")]
+        {
+            let Some(Output { target, fps }) = &self.output else {
+                return;
+            };
+            if self.frames.is_empty() {
+                return;
+            }
+            let result = match target.as_str() {
+                "terminal" => self.replay(*fps),
+                _ if target.ends_with(".gif") => self.write_gif(target, *fps),
+                _ => self.write_png_sequence(target),
+            };
+            if let Err(e) = result {
+                eprintln!("Could not write visualization to {}: {}", target, e);
+            }
+        }
+    }
+}
+
+#[cfg(feature = "visualize")]
+fn scale(frame: &Frame) -> usize {
+    let width = frame.bounds().map_or(1, |it| it.width().max(it.height())) as usize;
+    (600 / width).clamp(1, 16)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "visualize")]
+    use crate::util::Point;
+    #[cfg(feature = "visualize")]
+    use std::collections::HashSet;
+
+    #[test]
+    fn disabled_recorder_never_builds_frames() {
+        let mut recorder = Recorder::disabled();
+        recorder.record(|| panic!("Frame built for a disabled recorder"));
+        recorder.finish();
+    }
+
+    #[cfg(feature = "visualize")]
+    fn glider() -> Vec<Frame> {
+        [[(1, 0), (2, 1), (0, 2)], [(0, 0), (2, 1), (1, 2)]]
+            .iter()
+            .map(|cells| {
+                let set: HashSet<Point> = cells.iter().map(|(x, y)| Point::new(*x, *y)).collect();
+                Frame::new(&set).highlight([Point::new(1, 1)], Colour::Red)
+            })
+            .collect()
+    }
+
+    #[cfg(feature = "visualize")]
+    fn recorded(day: i32, target: String) -> Recorder {
+        let mut recorder = Recorder::new(day, Output { target, fps: 25 });
+        for frame in glider() {
+            recorder.record(|| frame);
+        }
+        recorder
+    }
+
+    #[cfg(feature = "visualize")]
+    #[test]
+    fn writes_a_gif_named_after_the_day() {
+        let dir = tempfile::tempdir().unwrap();
+        let target = dir.path().join("day{}.gif").to_str().unwrap().to_string();
+        recorded(7, target).finish();
+
+        let images = glider()
+            .iter()
+            .map(|it| it.to_image(scale(it), Colour::for_glyph))
+            .collect::<Vec<_>>();
+        assert_eq!(images[0].width(), 3 * 16);
+        let written = fs::read(dir.path().join("day7.gif")).unwrap();
+        assert_eq!(written, encode_gif(&images, 4).unwrap());
+    }
+
+    #[cfg(feature = "visualize")]
+    #[test]
+    fn writes_one_png_per_frame() {
+        let dir = tempfile::tempdir().unwrap();
+        let target = dir.path().join("frames-{}").to_str().unwrap().to_string();
+        recorded(4, target).finish();
+
+        let frames = dir.path().join("frames-4");
+        let mut names = fs::read_dir(&frames)
+            .unwrap()
+            .map(|it| it.unwrap().file_name().into_string().unwrap())
+            .collect::<Vec<_>>();
+        names.sort();
+        assert_eq!(names, vec!["frame_00000.png", "frame_00001.png"]);
+        for (name, frame) in names.iter().zip(glider()) {
+            let expected = frame.to_image(16, Colour::for_glyph).to_png();
+            assert_eq!(fs::read(frames.join(name)).unwrap(), expected);
+        }
+    }
+
+    #[cfg(feature = "visualize")]
+    #[test]
+    fn replays_every_frame_in_place() {
+        let recorder = recorded(1, "terminal".to_string());
+        assert_eq!(recorder.frames().len(), 2);
+        let mut out = vec![];
+        recorder.replay_to(&mut out, 1000).unwrap();
+
+        let out = String::from_utf8(out).unwrap();
+        assert_eq!(out.matches("\x1b[2J\x1b[H").count(), 2);
+        assert!(out.contains(".#.\n.\x1b[1;31m.\x1b[0m#\n#..\nFrame 1/2\n"));
+        assert!(out.ends_with("Frame 2/2\n"));
+    }
+}
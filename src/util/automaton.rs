@@ -0,0 +1,335 @@
+use crate::util::{GridAccess, Point};
+use array2d::Array2D;
+use rustc_hash::{FxHashMap, FxHashSet};
+use std::hash::Hash;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Neighbourhood {
+    Four,
+    Eight,
+    Custom(Vec<(i32, i32)>),
+}
+
+impl Neighbourhood {
+    pub fn offsets(&self) -> Vec<(i32, i32)> {
+        match self {
+            Neighbourhood::Four => vec![(0, -1), (1, 0), (0, 1), (-1, 0)],
+            Neighbourhood::Eight => vec![
+                (-1, -1),
+                (0, -1),
+                (1, -1),
+                (1, 0),
+                (1, 1),
+                (0, 1),
+                (-1, 1),
+                (-1, 0),
+            ],
+            Neighbourhood::Custom(offsets) => offsets.clone(),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Cycle {
+    /// Generation at which the repeating state first appeared. A fixpoint is a cycle of length 1
+    /// starting at its own generation.
+    pub start: usize,
+    pub length: usize,
+}
+
+/// Synchronous cellular automaton over an `Array2D`.
+///
+/// The rule gets the current cell and its in-bounds neighbours and returns the next value.
+pub struct Automaton<T, F> {
+    grid: Array2D<T>,
+    offsets: Vec<(i32, i32)>,
+    rule: F,
+    generation: usize,
+}
+
+impl<T, F> Automaton<T, F>
+where
+    T: Clone + PartialEq,
+    F: Fn(&T, &[&T]) -> T,
+{
+    pub fn new(grid: Array2D<T>, neighbourhood: Neighbourhood, rule: F) -> Self {
+        Automaton {
+            grid,
+            offsets: neighbourhood.offsets(),
+            rule,
+            generation: 0,
+        }
+    }
+
+    pub fn grid(&self) -> &Array2D<T> {
+        &self.grid
+    }
+
+    pub fn into_grid(self) -> Array2D<T> {
+        self.grid
+    }
+
+    /// The generation the grid is in. Steps at a fixpoint don't advance it, as the grid is then
+    /// every later generation at once.
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    pub fn count(&self, pred: impl Fn(&T) -> bool) -> usize {
+        self.grid
+            .elements_row_major_iter()
+            .filter(|it| pred(it))
+            .count()
+    }
+
+    fn next_value(&self, p: &Point) -> Option<T> {
+        let current = self.grid.get_point(p).unwrap();
+        let neighbours = self
+            .offsets
+            .iter()
+            .filter_map(|(dx, dy)| self.grid.get_point(&Point::new(p.x + dx, p.y + dy)))
+            .collect::<Vec<_>>();
+        let next = (self.rule)(current, &neighbours);
+        if next != *current { Some(next) } else { None }
+    }
+
+    /// Writes `changes` as the next generation; a generation without changes is no step at all.
+    fn apply(&mut self, changes: &[(Point, T)]) {
+        if changes.is_empty() {
+            return;
+        }
+        for (p, value) in changes {
+            self.grid
+                .set(p.y as usize, p.x as usize, value.clone())
+                .unwrap();
+        }
+        self.generation += 1;
+    }
+
+    /// Advances one generation and returns the number of cells that changed.
+    ///
+    /// The generation counter stays put if nothing changed, as the grid is at a fixpoint.
+    pub fn step(&mut self) -> usize {
+        let changes = self
+            .grid
+            .indices_row_major()
+            .map(|(row, col)| Point::from_index(col, row))
+            .filter_map(|p| self.next_value(&p).map(|v| (p, v)))
+            .collect::<Vec<_>>();
+        self.apply(&changes);
+        changes.len()
+    }
+
+    /// Steps `n` times and returns the total number of cell changes. Once a fixpoint is reached
+    /// the remaining steps change nothing and leave `generation` where it is.
+    pub fn run(&mut self, n: usize) -> usize {
+        (0..n).map(|_| self.step()).sum()
+    }
+
+    /// Steps until nothing changes and returns the number of generations that changed something.
+    pub fn run_until_fixpoint(&mut self) -> usize {
+        let mut steps = 0;
+        while self.step() > 0 {
+            steps += 1;
+        }
+        steps
+    }
+
+    /// Like `run_until_fixpoint`, but after the first generation only re-evaluates
+    /// the neighbours of cells that changed in the previous one.
+    ///
+    /// Only valid for rules where a cell's next value depends on nothing but itself and its
+    /// neighbourhood.
+    pub fn run_until_fixpoint_worklist(&mut self) -> usize {
+        self.run_until_fixpoint_worklist_observed(|_| {})
+    }
+
+    /// `run_until_fixpoint_worklist`, calling `observe` with the grid after every generation.
+    pub fn run_until_fixpoint_worklist_observed(
+        &mut self,
+        mut observe: impl FnMut(&Array2D<T>),
+    ) -> usize {
+        let mut candidates = self
+            .grid
+            .indices_row_major()
+            .map(|(row, col)| Point::from_index(col, row))
+            .collect::<Vec<_>>();
+        let mut steps = 0;
+        loop {
+            let changes = candidates
+                .iter()
+                .filter_map(|p| self.next_value(p).map(|v| (*p, v)))
+                .collect::<Vec<_>>();
+            if changes.is_empty() {
+                return steps;
+            }
+            self.apply(&changes);
+            observe(&self.grid);
+            steps += 1;
+
+            let mut next: FxHashSet<Point> = FxHashSet::default();
+            for (p, _) in &changes {
+                next.insert(*p);
+                for (dx, dy) in &self.offsets {
+                    let n = Point::new(p.x - dx, p.y - dy);
+                    if self.grid.get_point(&n).is_some() {
+                        next.insert(n);
+                    }
+                }
+            }
+            candidates = next.into_iter().collect();
+        }
+    }
+}
+
+impl<T, F> Automaton<T, F>
+where
+    T: Clone + Eq + Hash,
+    F: Fn(&T, &[&T]) -> T,
+{
+    /// Steps until a previously seen state comes back and leaves the grid at `start + length`.
+    ///
+    /// Every state is kept, so a repeat is a real one and not just a hash collision.
+    pub fn find_cycle(&mut self) -> Cycle {
+        let mut seen: FxHashMap<Array2D<T>, usize> = FxHashMap::default();
+        loop {
+            if let Some(start) = seen.insert(self.grid.clone(), self.generation) {
+                return Cycle {
+                    start,
+                    length: self.generation - start,
+                };
+            }
+            if self.step() == 0 {
+                // A fixpoint repeats itself every generation
+                return Cycle {
+                    start: self.generation,
+                    length: 1,
+                };
+            }
+        }
+    }
+
+    /// Runs to generation `n`, skipping ahead through the cycle once one is found. The grid then
+    /// is in the state of generation `n`, and so is the counter, even at a fixpoint.
+    pub fn run_to_generation(&mut self, n: usize) {
+        let mut seen: FxHashMap<Array2D<T>, usize> = FxHashMap::default();
+        while self.generation < n {
+            if let Some(start) = seen.insert(self.grid.clone(), self.generation) {
+                let length = self.generation - start;
+                let remaining = (n - self.generation) % length;
+                self.run(remaining);
+                self.generation = n;
+                return;
+            }
+            if self.step() == 0 {
+                self.generation = n;
+                return;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn life(grid: &[&str]) -> Array2D<char> {
+        let rows = grid
+            .iter()
+            .map(|it| it.chars().collect())
+            .collect::<Vec<_>>();
+        Array2D::from_rows(&rows).unwrap()
+    }
+
+    fn conway(c: &char, ns: &[&char]) -> char {
+        let alive = ns.iter().filter(|it| ***it == '#').count();
+        match (c, alive) {
+            ('#', 2) | (_, 3) => '#',
+            _ => '.',
+        }
+    }
+
+    #[test]
+    fn blinker_has_period_two() {
+        let grid = life(&[".....", "..#..", "..#..", "..#..", "....."]);
+        let mut automaton = Automaton::new(grid.clone(), Neighbourhood::Eight, conway);
+
+        assert_eq!(automaton.step(), 4);
+        assert_ne!(automaton.grid(), &grid);
+        assert_eq!(
+            automaton.find_cycle(),
+            Cycle {
+                start: 1,
+                length: 2
+            }
+        );
+
+        let mut automaton = Automaton::new(grid.clone(), Neighbourhood::Eight, conway);
+        automaton.run_to_generation(1001);
+        assert_eq!(automaton.generation(), 1001);
+        assert_ne!(automaton.grid(), &grid);
+    }
+
+    #[test]
+    fn worklist_matches_full_steps() {
+        let grid = life(&["@@@..", "@@@@.", ".@@@@", "..@@@"]);
+        let peel = |c: &char, ns: &[&char]| {
+            if *c == '@' && ns.iter().filter(|it| ***it == '@').count() < 4 {
+                '.'
+            } else {
+                *c
+            }
+        };
+        let mut full = Automaton::new(grid.clone(), Neighbourhood::Eight, peel);
+        let mut worklist = Automaton::new(grid, Neighbourhood::Eight, peel);
+
+        let steps = full.run_until_fixpoint();
+        assert_eq!(worklist.run_until_fixpoint_worklist(), steps);
+        assert_eq!(full.grid(), worklist.grid());
+        assert_eq!(full.generation(), worklist.generation());
+    }
+
+    fn spread(c: &char, ns: &[&char]) -> char {
+        if ns.iter().any(|it| **it == '#') {
+            '#'
+        } else {
+            *c
+        }
+    }
+
+    #[test]
+    fn four_neighbourhood_spreads_orthogonally() {
+        let grid = life(&[".....", ".....", "..#..", ".....", "....."]);
+        let mut automaton = Automaton::new(grid, Neighbourhood::Four, spread);
+
+        assert_eq!(automaton.step(), 4);
+        assert_eq!(
+            automaton.grid(),
+            &life(&[".....", "..#..", ".###.", "..#..", "....."])
+        );
+        assert_eq!(automaton.run_until_fixpoint(), 3);
+        assert_eq!(automaton.count(|it| *it == '#'), 25);
+        assert_eq!(automaton.generation(), 4);
+        assert_eq!(automaton.step(), 0);
+        assert_eq!(automaton.generation(), 4);
+        assert_eq!(
+            automaton.find_cycle(),
+            Cycle {
+                start: 4,
+                length: 1
+            }
+        );
+        automaton.run_to_generation(100);
+        assert_eq!(automaton.generation(), 100);
+    }
+
+    #[test]
+    fn custom_neighbourhood_only_looks_left() {
+        let grid = life(&["#....", "..#..", "....#"]);
+        let mut automaton = Automaton::new(grid, Neighbourhood::Custom(vec![(-1, 0)]), spread);
+
+        assert_eq!(automaton.run_until_fixpoint(), 4);
+        assert_eq!(automaton.grid(), &life(&["#####", "..###", "....#"]));
+        assert_eq!(automaton.generation(), 4);
+    }
+}
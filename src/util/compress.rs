@@ -0,0 +1,152 @@
+use num::PrimInt;
+
+/// Coordinate compression over `N` independent axes.
+///
+/// Every distinct value of an axis gets a dense index in sorted order. With `gaps`, the value
+/// with rank `i` maps to `2 * i` and the odd index in between stands for all values strictly
+/// between two neighbours, so shapes keep their topology (e.g. for flood fills) and every
+/// compressed cell knows the real area it covers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Compressor<T, const N: usize> {
+    axes: [Vec<T>; N],
+    gaps: bool,
+}
+
+impl<T: PrimInt, const N: usize> Compressor<T, N> {
+    pub fn new(points: impl IntoIterator<Item = [T; N]>, gaps: bool) -> Compressor<T, N> {
+        let mut axes: [Vec<T>; N] = std::array::from_fn(|_| vec![]);
+        for p in points {
+            for (axis, v) in axes.iter_mut().zip(p) {
+                axis.push(v);
+            }
+        }
+        for axis in &mut axes {
+            axis.sort_unstable();
+            axis.dedup();
+        }
+        Compressor { axes, gaps }
+    }
+
+    /// Number of compressed indices along `axis`.
+    pub fn len(&self, axis: usize) -> usize {
+        let values = self.axes[axis].len();
+        if self.gaps {
+            (2 * values).saturating_sub(1)
+        } else {
+            values
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.axes.iter().any(|it| it.is_empty())
+    }
+
+    /// Compressed index of `v` on `axis`, or `None` if `v` was not one of the given values.
+    pub fn compress_axis(&self, axis: usize, v: T) -> Option<usize> {
+        let rank = self.axes[axis].binary_search(&v).ok()?;
+        Some(if self.gaps { 2 * rank } else { rank })
+    }
+
+    /// Compressed index of the cell containing `v`, including gap cells, or `None` outside of
+    /// the range of given values.
+    pub fn locate_axis(&self, axis: usize, v: T) -> Option<usize> {
+        let values = &self.axes[axis];
+        match values.binary_search(&v) {
+            Ok(rank) => Some(if self.gaps { 2 * rank } else { rank }),
+            Err(rank) if self.gaps && rank > 0 && rank < values.len() => Some(2 * rank - 1),
+            Err(_) => None,
+        }
+    }
+
+    /// Smallest real value covered by compressed index `c` on `axis`.
+    pub fn decompress_axis(&self, axis: usize, c: usize) -> T {
+        if self.gaps {
+            let v = self.axes[axis][c / 2];
+            if c.is_multiple_of(2) { v } else { v + T::one() }
+        } else {
+            self.axes[axis][c]
+        }
+    }
+
+    /// Number of real values covered by compressed index `c` on `axis`; gap cells between two
+    /// adjacent values cover none.
+    pub fn cell_size(&self, axis: usize, c: usize) -> T {
+        if self.gaps && c % 2 == 1 {
+            self.axes[axis][c / 2 + 1] - self.axes[axis][c / 2] - T::one()
+        } else {
+            T::one()
+        }
+    }
+
+    pub fn compress(&self, p: [T; N]) -> Option<[usize; N]> {
+        let mut c = [0; N];
+        for (axis, v) in p.into_iter().enumerate() {
+            c[axis] = self.compress_axis(axis, v)?;
+        }
+        Some(c)
+    }
+
+    pub fn locate(&self, p: [T; N]) -> Option<[usize; N]> {
+        let mut c = [0; N];
+        for (axis, v) in p.into_iter().enumerate() {
+            c[axis] = self.locate_axis(axis, v)?;
+        }
+        Some(c)
+    }
+
+    pub fn decompress(&self, c: [usize; N]) -> [T; N] {
+        std::array::from_fn(|axis| self.decompress_axis(axis, c[axis]))
+    }
+
+    /// Number of real points covered by the compressed cell `c`.
+    pub fn area(&self, c: [usize; N]) -> T {
+        (0..N).fold(T::one(), |acc, axis| acc * self.cell_size(axis, c[axis]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compresses_with_duplicates() {
+        let c = Compressor::new([[100i64], [-5], [100], [7]], false);
+
+        assert_eq!(c.len(0), 3);
+        assert_eq!(c.compress([-5]), Some([0]));
+        assert_eq!(c.compress([100]), Some([2]));
+        assert_eq!(c.compress([8]), None);
+        assert_eq!(c.decompress([1]), [7]);
+        assert_eq!(c.locate_axis(0, 8), None);
+    }
+
+    #[test]
+    fn gap_cells_cover_the_real_area() {
+        let points = [
+            [7i64, 1],
+            [11, 1],
+            [11, 7],
+            [9, 7],
+            [9, 5],
+            [2, 5],
+            [2, 3],
+            [7, 3],
+        ];
+        let c = Compressor::new(points, true);
+
+        assert_eq!((c.len(0), c.len(1)), (7, 7));
+        assert_eq!(c.compress([9, 5]), Some([4, 4]));
+        assert_eq!(c.locate([8, 4]), Some([3, 3]));
+        assert_eq!(c.locate([1, 4]), None);
+        assert_eq!(c.decompress([3, 3]), [8, 4]);
+        for p in points {
+            assert_eq!(c.decompress(c.compress(p).unwrap()), p);
+        }
+        let total: i64 = (0..c.len(0))
+            .flat_map(|x| (0..c.len(1)).map(move |y| [x, y]))
+            .map(|it| c.area(it))
+            .sum();
+        assert_eq!(total, (11 - 2 + 1) * (7 - 1 + 1));
+        assert_eq!(c.area([1, 1]), 4);
+    }
+}
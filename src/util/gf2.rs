@@ -0,0 +1,260 @@
+use std::ops::BitXorAssign;
+
+/// Fixed length vector over GF(2), packed into 64 bit words.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BitRow {
+    len: usize,
+    words: Vec<u64>,
+}
+
+impl BitXorAssign<&BitRow> for BitRow {
+    fn bitxor_assign(&mut self, rhs: &BitRow) {
+        for (a, b) in self.words.iter_mut().zip(&rhs.words) {
+            *a ^= b;
+        }
+    }
+}
+
+impl BitRow {
+    pub fn new(len: usize) -> BitRow {
+        BitRow {
+            len,
+            words: vec![0; len.div_ceil(64)],
+        }
+    }
+
+    pub fn from_indices(len: usize, ones: impl IntoIterator<Item = usize>) -> BitRow {
+        let mut row = BitRow::new(len);
+        for i in ones {
+            row.set(i, true);
+        }
+        row
+    }
+
+    pub fn from_bools(bits: &[bool]) -> BitRow {
+        BitRow::from_indices(bits.len(), (0..bits.len()).filter(|it| bits[*it]))
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn get(&self, i: usize) -> bool {
+        assert!(
+            i < self.len,
+            "Index {} out of range for {} bits",
+            i,
+            self.len
+        );
+        self.words[i / 64] >> (i % 64) & 1 == 1
+    }
+
+    pub fn set(&mut self, i: usize, value: bool) {
+        assert!(
+            i < self.len,
+            "Index {} out of range for {} bits",
+            i,
+            self.len
+        );
+        if value {
+            self.words[i / 64] |= 1 << (i % 64);
+        } else {
+            self.words[i / 64] &= !(1 << (i % 64));
+        }
+    }
+
+    pub fn flip(&mut self, i: usize) {
+        self.set(i, !self.get(i));
+    }
+
+    pub fn count_ones(&self) -> usize {
+        self.words.iter().map(|it| it.count_ones() as usize).sum()
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.words.iter().all(|it| *it == 0)
+    }
+
+    /// Indices of the set bits in ascending order.
+    pub fn ones(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.len).filter(|it| self.get(*it))
+    }
+}
+
+/// Linear system `A x = b` over GF(2), one equation per row.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Gf2System {
+    vars: usize,
+    /// Coefficients with the right hand side as an extra last bit.
+    rows: Vec<BitRow>,
+}
+
+/// All solutions of a `Gf2System`: `particular` plus any combination of the `null_space` basis.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Gf2Solution {
+    pub particular: BitRow,
+    pub null_space: Vec<BitRow>,
+}
+
+impl Gf2System {
+    pub fn new(vars: usize) -> Gf2System {
+        Gf2System { vars, rows: vec![] }
+    }
+
+    /// Adds the equation "the sum of the variables in `vars` equals `rhs`".
+    pub fn add_equation(&mut self, vars: impl IntoIterator<Item = usize>, rhs: bool) {
+        let mut row = BitRow::from_indices(self.vars + 1, vars);
+        row.set(self.vars, rhs);
+        self.rows.push(row);
+    }
+
+    /// Reduces the rows to reduced row echelon form and returns the pivot column of each of the
+    /// leading rows; the coefficients of all rows after those are zero.
+    fn eliminate(&mut self) -> Vec<usize> {
+        let mut pivots = vec![];
+        let mut rank = 0;
+        for col in 0..self.vars {
+            let Some(pivot) = (rank..self.rows.len()).find(|it| self.rows[*it].get(col)) else {
+                continue;
+            };
+            self.rows.swap(rank, pivot);
+            let pivot_row = self.rows[rank].clone();
+            for (i, row) in self.rows.iter_mut().enumerate() {
+                if i != rank && row.get(col) {
+                    *row ^= &pivot_row;
+                }
+            }
+            pivots.push(col);
+            rank += 1;
+        }
+        pivots
+    }
+
+    /// Rank of the coefficient matrix.
+    pub fn rank(&self) -> usize {
+        self.clone().eliminate().len()
+    }
+
+    /// `None` if the equations contradict each other.
+    pub fn solve(&self) -> Option<Gf2Solution> {
+        let mut reduced = self.clone();
+        let pivots = reduced.eliminate();
+        // A row without pivot and a set right hand side reads `0 = 1`
+        if reduced.rows[pivots.len()..]
+            .iter()
+            .any(|it| it.get(self.vars))
+        {
+            return None;
+        }
+        let mut particular = BitRow::new(self.vars);
+        for (row, col) in reduced.rows.iter().zip(&pivots) {
+            particular.set(*col, row.get(self.vars));
+        }
+        let null_space = (0..self.vars)
+            .filter(|it| !pivots.contains(it))
+            .map(|free| {
+                let mut v = BitRow::new(self.vars);
+                v.set(free, true);
+                for (row, col) in reduced.rows.iter().zip(&pivots) {
+                    v.set(*col, row.get(free));
+                }
+                v
+            })
+            .collect();
+        Some(Gf2Solution {
+            particular,
+            null_space,
+        })
+    }
+}
+
+impl Gf2Solution {
+    /// Number of free variables.
+    pub fn dimension(&self) -> usize {
+        self.null_space.len()
+    }
+
+    /// The solution with the fewest set variables, trying all `2^dimension()` of them in Gray
+    /// code order so each step is a single row XOR.
+    pub fn min_weight(&self) -> BitRow {
+        assert!(
+            self.dimension() < 32,
+            "Too many free variables to enumerate"
+        );
+        let mut current = self.particular.clone();
+        let mut best = current.clone();
+        for i in 1u64..1 << self.dimension() {
+            current ^= &self.null_space[i.trailing_zeros() as usize];
+            if current.count_ones() < best.count_ones() {
+                best = current.clone();
+            }
+        }
+        best
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn packs_bits_across_words() {
+        let mut row = BitRow::from_indices(130, [0, 63, 64, 129]);
+        row.flip(63);
+        row ^= &BitRow::from_indices(130, [1, 129]);
+
+        assert_eq!(row.ones().collect::<Vec<_>>(), vec![0, 1, 64]);
+        assert_eq!(row.count_ones(), 3);
+        assert_eq!(
+            BitRow::from_bools(&[false, true])
+                .ones()
+                .collect::<Vec<_>>(),
+            vec![1]
+        );
+    }
+
+    #[test]
+    fn finds_minimum_weight_solutions() {
+        // [.##.] (3) (1,3) (2) (2,3) (0,2) (0,1)
+        let buttons: [&[usize]; 6] = [&[3], &[1, 3], &[2], &[2, 3], &[0, 2], &[0, 1]];
+        let mut system = Gf2System::new(buttons.len());
+        for (light, on) in [false, true, true, false].into_iter().enumerate() {
+            system.add_equation(
+                (0..buttons.len()).filter(|b| buttons[*b].contains(&light)),
+                on,
+            );
+        }
+
+        let solution = system.solve().unwrap();
+        assert_eq!(system.rank(), 4);
+        assert_eq!(solution.dimension(), 2);
+        assert_eq!(solution.min_weight().count_ones(), 2);
+        for i in 0..4 {
+            let mut x = solution.particular.clone();
+            if i & 1 == 1 {
+                x ^= &solution.null_space[0];
+            }
+            if i & 2 == 2 {
+                x ^= &solution.null_space[1];
+            }
+            for light in 0..4 {
+                let toggles = x.ones().filter(|b| buttons[*b].contains(&light)).count();
+                assert_eq!(toggles % 2 == 1, [false, true, true, false][light]);
+            }
+        }
+    }
+
+    #[test]
+    fn detects_contradictions() {
+        let mut system = Gf2System::new(2);
+        system.add_equation([0, 1], true);
+        system.add_equation([0], false);
+        assert!(system.solve().is_some());
+        system.add_equation([1], false);
+        assert_eq!(system.solve(), None);
+    }
+}
@@ -0,0 +1,201 @@
+use crate::util::union_find::UnionFind;
+use itertools::Itertools;
+
+/// Undirected weighted edge between the nodes `a` and `b` of `0..n`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct Edge<W> {
+    pub a: usize,
+    pub b: usize,
+    pub weight: W,
+}
+
+impl<W> Edge<W> {
+    pub fn new(a: usize, b: usize, weight: W) -> Edge<W> {
+        Edge { a, b, weight }
+    }
+}
+
+/// An edge that joined two components during `Kruskal`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct Merge<W> {
+    pub edge: Edge<W>,
+    /// Sizes of the components containing `edge.a` and `edge.b` before the merge.
+    pub sizes: (usize, usize),
+    /// Number of components left after the merge.
+    pub components: usize,
+    /// Number of edges looked at so far, this one included.
+    pub considered: usize,
+}
+
+/// Kruskal's algorithm as an iterator over the merges, i.e. the MST edges in increasing order.
+///
+/// Edges that connect nodes of the same component are skipped but still counted in
+/// `considered`, so "the first 1000 edges" and "the edge that connected everything" are both
+/// simple queries.
+pub struct Kruskal<I> {
+    edges: I,
+    components: UnionFind,
+    considered: usize,
+}
+
+impl<W: Ord + Copy> Kruskal<std::vec::IntoIter<Edge<W>>> {
+    /// Sorts `edges` by weight first, keeping the given order for equal weights.
+    pub fn from_unsorted(n: usize, mut edges: Vec<Edge<W>>) -> Self {
+        edges.sort_by_key(|it| it.weight);
+        Kruskal::new(n, edges)
+    }
+}
+
+impl<W: Ord + Copy, I: Iterator<Item = Edge<W>>> Kruskal<I> {
+    /// `edges` must already be sorted by weight.
+    pub fn new(n: usize, edges: impl IntoIterator<IntoIter = I>) -> Self {
+        Kruskal {
+            edges: edges.into_iter(),
+            components: UnionFind::new(n),
+            considered: 0,
+        }
+    }
+
+    pub fn components(&self) -> &UnionFind {
+        &self.components
+    }
+
+    pub fn considered(&self) -> usize {
+        self.considered
+    }
+
+    /// Looks at the next `count` edges (fewer if they run out) and returns the merges among them.
+    pub fn consider(&mut self, count: usize) -> Vec<Merge<W>> {
+        (0..count).map_while(|_| self.step()).flatten().collect()
+    }
+
+    /// Looks at the next edge. `None` when there are no edges left, `Some(None)` if it was skipped.
+    pub fn step(&mut self) -> Option<Option<Merge<W>>> {
+        let edge = self.edges.next()?;
+        self.considered += 1;
+        let sizes = (
+            self.components.size_of(edge.a),
+            self.components.size_of(edge.b),
+        );
+        Some(self.components.union(edge.a, edge.b).then(|| Merge {
+            edge,
+            sizes,
+            components: self.components.component_count(),
+            considered: self.considered,
+        }))
+    }
+}
+
+impl<W: Ord + Copy, I: Iterator<Item = Edge<W>>> Iterator for Kruskal<I> {
+    type Item = Merge<W>;
+
+    fn next(&mut self) -> Option<Merge<W>> {
+        if self.components.component_count() <= 1 {
+            return None;
+        }
+        loop {
+            if let Some(merge) = self.step()? {
+                return Some(merge);
+            }
+        }
+    }
+}
+
+/// Minimum spanning forest of the nodes `0..n`, edges in increasing order of weight.
+pub fn kruskal<W: Ord + Copy>(n: usize, edges: Vec<Edge<W>>) -> Vec<Edge<W>> {
+    Kruskal::from_unsorted(n, edges).map(|it| it.edge).collect()
+}
+
+/// Minimum spanning tree of the complete graph on `0..n` in O(n²), without listing all edges.
+///
+/// Edges are in the order they were added to the tree starting from node 0, not by weight.
+pub fn prim<W: Ord + Copy>(n: usize, weight: impl Fn(usize, usize) -> W) -> Vec<Edge<W>> {
+    let mut in_tree = vec![false; n];
+    // Cheapest known edge into the tree for every node outside of it
+    let mut best: Vec<Option<Edge<W>>> = vec![None; n];
+    let mut tree = vec![];
+    let mut next = (n > 0).then_some(0);
+    while let Some(node) = next {
+        in_tree[node] = true;
+        if let Some(edge) = best[node] {
+            tree.push(edge);
+        }
+        for other in (0..n).filter(|it| !in_tree[*it]) {
+            let w = weight(node, other);
+            if best[other].is_none_or(|it| w < it.weight) {
+                best[other] = Some(Edge::new(node, other, w));
+            }
+        }
+        next = (0..n)
+            .filter(|it| !in_tree[*it])
+            .min_by_key(|it| best[*it].map(|e| e.weight));
+    }
+    tree
+}
+
+/// Every pair `i < j` of `0..n` as an edge, sorted by `weight`.
+pub fn complete_graph<W: Ord + Copy>(n: usize, weight: impl Fn(usize, usize) -> W) -> Vec<Edge<W>> {
+    (0..n)
+        .tuple_combinations()
+        .map(|(a, b)| Edge::new(a, b, weight(a, b)))
+        .sorted_by_key(|it| it.weight)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edges() -> Vec<Edge<u32>> {
+        [
+            (0, 1, 4),
+            (1, 2, 1),
+            (0, 2, 3),
+            (2, 3, 7),
+            (1, 3, 5),
+            (4, 5, 2),
+        ]
+        .map(|(a, b, w)| Edge::new(a, b, w))
+        .to_vec()
+    }
+
+    #[test]
+    fn reports_merge_events() {
+        let mut k = Kruskal::from_unsorted(6, edges());
+        let first = k.consider(3);
+
+        assert_eq!(
+            first.iter().map(|it| it.edge.weight).collect_vec(),
+            vec![1, 2, 3]
+        );
+        assert_eq!(first[2].sizes, (1, 2));
+        assert_eq!(first[2].components, 3);
+        assert_eq!(k.components().largest(2), vec![3, 2]);
+
+        let rest = k.collect_vec();
+        assert_eq!(rest.len(), 1);
+        assert_eq!(rest[0].edge, Edge::new(1, 3, 5));
+        assert_eq!(rest[0].considered, 5);
+        assert_eq!(rest[0].components, 2);
+    }
+
+    #[test]
+    fn kruskal_and_prim_agree_on_complete_graphs() {
+        let xs = [3i64, 17, -4, 8, 30, 11, 0];
+        let weight = |a: usize, b: usize| (xs[a] - xs[b]).abs();
+        let sorted = complete_graph(xs.len(), weight);
+
+        let total = |edges: &[Edge<i64>]| edges.iter().map(|it| it.weight).sum::<i64>();
+        let by_kruskal = kruskal(xs.len(), sorted.clone());
+        let by_prim = prim(xs.len(), weight);
+        assert_eq!(by_kruskal.len(), xs.len() - 1);
+        assert_eq!(by_prim.len(), xs.len() - 1);
+        assert_eq!(total(&by_kruskal), 34);
+        assert_eq!(total(&by_prim), 34);
+        assert!(by_kruskal.is_sorted_by_key(|it| it.weight));
+        assert_eq!(
+            Kruskal::new(xs.len(), sorted).last().unwrap().edge.weight,
+            13
+        );
+    }
+}
@@ -0,0 +1,366 @@
+use crate::util::Point;
+use crate::util::render::{Colour, Frame, Svg};
+use crate::util::sparse::SparseGrid;
+use std::fmt::{Display, Formatter};
+use std::ops::{Add, Mul, Neg, Sub};
+
+/// Hex cell in axial coordinates. The implied third cube coordinate is `s = -q - r`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, Ord, PartialOrd, Default)]
+pub struct Hex {
+    pub q: i32,
+    pub r: i32,
+}
+
+/// Hex cell in cube coordinates, always satisfying `q + r + s == 0`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, Default)]
+pub struct Cube {
+    pub q: i32,
+    pub r: i32,
+    pub s: i32,
+}
+
+impl From<Hex> for Cube {
+    fn from(h: Hex) -> Cube {
+        Cube {
+            q: h.q,
+            r: h.r,
+            s: -h.q - h.r,
+        }
+    }
+}
+
+impl From<Cube> for Hex {
+    fn from(c: Cube) -> Hex {
+        Hex::new(c.q, c.r)
+    }
+}
+
+impl Display for Hex {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "<{}, {}>", self.q, self.r)
+    }
+}
+
+impl Add for Hex {
+    type Output = Hex;
+
+    fn add(self, rhs: Hex) -> Hex {
+        Hex::new(self.q + rhs.q, self.r + rhs.r)
+    }
+}
+
+impl Sub for Hex {
+    type Output = Hex;
+
+    fn sub(self, rhs: Hex) -> Hex {
+        Hex::new(self.q - rhs.q, self.r - rhs.r)
+    }
+}
+
+impl Mul<i32> for Hex {
+    type Output = Hex;
+
+    fn mul(self, rhs: i32) -> Hex {
+        Hex::new(self.q * rhs, self.r * rhs)
+    }
+}
+
+impl Neg for Hex {
+    type Output = Hex;
+
+    fn neg(self) -> Hex {
+        Hex::new(-self.q, -self.r)
+    }
+}
+
+impl Hex {
+    pub fn new(q: i32, r: i32) -> Hex {
+        Hex { q, r }
+    }
+
+    pub fn s(&self) -> i32 {
+        -self.q - self.r
+    }
+
+    pub fn to_cube(self) -> Cube {
+        self.into()
+    }
+
+    /// Number of steps from the origin.
+    pub fn length(&self) -> i32 {
+        (self.q.abs() + self.r.abs() + self.s().abs()) / 2
+    }
+
+    pub fn distance(&self, other: &Hex) -> i32 {
+        (*self - *other).length()
+    }
+
+    pub fn mv(&self, d: HexDir) -> Hex {
+        *self + d.offset()
+    }
+
+    pub fn neighbours(&self) -> [Hex; 6] {
+        HexDir::ALL.map(|d| self.mv(d))
+    }
+
+    /// Rotates by 60° clockwise around the origin.
+    pub fn rotate_cw(&self) -> Hex {
+        let c = self.to_cube();
+        Hex::new(-c.r, -c.s)
+    }
+
+    /// Rotates by 60° counter-clockwise around the origin.
+    pub fn rotate_ccw(&self) -> Hex {
+        let c = self.to_cube();
+        Hex::new(-c.s, -c.q)
+    }
+
+    /// Rotates clockwise around `center` by `sixth_turns` × 60°; negative values rotate
+    /// counter-clockwise.
+    pub fn rotate_around(&self, center: &Hex, sixth_turns: i32) -> Hex {
+        let mut h = *self - *center;
+        for _ in 0..sixth_turns.rem_euclid(6) {
+            h = h.rotate_cw();
+        }
+        h + *center
+    }
+
+    /// All cells at exactly `radius` steps, going counter-clockwise. Radius 0 is the cell itself.
+    pub fn ring(&self, radius: u32) -> Vec<Hex> {
+        if radius == 0 {
+            return vec![*self];
+        }
+        let mut h = *self + HexDir::ALL[4].offset() * radius as i32;
+        let mut ring = Vec::with_capacity(6 * radius as usize);
+        for d in HexDir::ALL {
+            for _ in 0..radius {
+                ring.push(h);
+                h = h.mv(d);
+            }
+        }
+        ring
+    }
+
+    /// All cells within `radius` steps, ring by ring starting with the cell itself.
+    pub fn spiral(&self, radius: u32) -> Vec<Hex> {
+        (0..=radius).flat_map(|it| self.ring(it)).collect()
+    }
+}
+
+/// One of the six neighbour directions, numbered counter-clockwise from axial `(+1, 0)`.
+///
+/// Which compass names they get depends on the `Layout`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct HexDir(u8);
+
+impl HexDir {
+    pub const ALL: [HexDir; 6] = [
+        HexDir(0),
+        HexDir(1),
+        HexDir(2),
+        HexDir(3),
+        HexDir(4),
+        HexDir(5),
+    ];
+
+    pub fn offset(&self) -> Hex {
+        let (q, r) = [(1, 0), (1, -1), (0, -1), (-1, 0), (-1, 1), (0, 1)][self.0 as usize];
+        Hex::new(q, r)
+    }
+
+    pub fn cw(&self) -> HexDir {
+        self.rotate(1)
+    }
+
+    pub fn ccw(&self) -> HexDir {
+        self.rotate(-1)
+    }
+
+    pub fn opposite(&self) -> HexDir {
+        self.rotate(3)
+    }
+
+    /// Turns clockwise by `sixth_turns` × 60°; negative values turn counter-clockwise.
+    pub fn rotate(&self, sixth_turns: i32) -> HexDir {
+        HexDir((self.0 as i32 - sixth_turns).rem_euclid(6) as u8)
+    }
+}
+
+/// Orientation of the hexagons, which decides direction names and how they are drawn.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum Layout {
+    /// Rows of hexes with a corner at the top: `e`, `ne`, `nw`, `w`, `sw`, `se`.
+    PointyTop,
+    /// Columns of hexes with an edge at the top: `se`, `ne`, `n`, `nw`, `sw`, `s`.
+    FlatTop,
+}
+
+impl Layout {
+    fn names(&self) -> [&'static str; 6] {
+        match self {
+            Layout::PointyTop => ["e", "ne", "nw", "w", "sw", "se"],
+            Layout::FlatTop => ["se", "ne", "n", "nw", "sw", "s"],
+        }
+    }
+
+    pub fn dir_name(&self, d: HexDir) -> &'static str {
+        self.names()[d.0 as usize]
+    }
+
+    /// Parses a single direction like `ne`, ignoring case.
+    pub fn parse_dir(&self, s: &str) -> Option<HexDir> {
+        let s = s.trim().to_ascii_lowercase();
+        self.names()
+            .iter()
+            .position(|it| *it == s)
+            .map(|it| HexDir(it as u8))
+    }
+
+    /// Parses directions separated by commas or whitespace, or written without any separator
+    /// like `nwwswee`.
+    pub fn parse_path(&self, s: &str) -> Option<Vec<HexDir>> {
+        let mut path = vec![];
+        for token in s.split(|c: char| c == ',' || c.is_whitespace()) {
+            let mut rest = token;
+            while !rest.is_empty() {
+                let (d, len) = [2, 1]
+                    .into_iter()
+                    .filter(|len| *len <= rest.len())
+                    .find_map(|len| Some((self.parse_dir(rest.get(..len)?)?, len)))?;
+                path.push(d);
+                rest = &rest[len..];
+            }
+        }
+        Some(path)
+    }
+
+    /// Position on a square grid with doubled columns (pointy top) or doubled rows (flat top).
+    pub fn doubled(&self, h: &Hex) -> Point {
+        match self {
+            Layout::PointyTop => Point::new(2 * h.q + h.r, h.r),
+            Layout::FlatTop => Point::new(h.q, 2 * h.r + h.q),
+        }
+    }
+
+    /// Inverse of `doubled`, or `None` for the unused half of the doubled grid.
+    pub fn at_doubled(&self, p: &Point) -> Option<Hex> {
+        let h = match self {
+            Layout::PointyTop => Hex::new((p.x - p.y).div_euclid(2), p.y),
+            Layout::FlatTop => Hex::new(p.x, (p.y - p.x).div_euclid(2)),
+        };
+        (self.doubled(&h) == *p).then_some(h)
+    }
+
+    /// Draws each cell's glyph at its doubled position, leaving the gaps blank.
+    pub fn ascii(&self, cells: impl IntoIterator<Item = (Hex, char)>) -> Frame {
+        let grid: SparseGrid<char> = cells
+            .into_iter()
+            .map(|(h, c)| (self.doubled(&h), c))
+            .collect();
+        grid.frame(|it| it.copied().unwrap_or(' '))
+    }
+
+    /// Centre of a cell in SVG units. A hexagon is 14 wide and 16 high (pointy top), which is
+    /// close enough to regular.
+    pub fn center(&self, h: &Hex) -> (i64, i64) {
+        let d = self.doubled(h);
+        match self {
+            Layout::PointyTop => (7 * d.x as i64, 12 * d.y as i64),
+            Layout::FlatTop => (12 * d.x as i64, 7 * d.y as i64),
+        }
+    }
+
+    pub fn corners(&self, h: &Hex) -> [(i64, i64); 6] {
+        let (x, y) = self.center(h);
+        let pointy = [(0, -8), (7, -4), (7, 4), (0, 8), (-7, 4), (-7, -4)];
+        match self {
+            Layout::PointyTop => pointy.map(|(dx, dy)| (x + dx, y + dy)),
+            Layout::FlatTop => pointy.map(|(dy, dx)| (x + dx, y + dy)),
+        }
+    }
+
+    /// One filled hexagon per cell.
+    pub fn svg(&self, cells: impl IntoIterator<Item = (Hex, Colour)>) -> Svg {
+        let mut svg = Svg::new();
+        for (h, colour) in cells {
+            svg.polygon(self.corners(&h), colour, Colour::Grey);
+        }
+        svg
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn measures_rings_and_rotations() {
+        let center = Hex::new(2, -1);
+
+        assert_eq!(center.ring(0), vec![center]);
+        assert_eq!(center.ring(3).len(), 18);
+        assert!(center.ring(3).iter().all(|it| it.distance(&center) == 3));
+        assert_eq!(center.spiral(2).len(), 19);
+        assert_eq!(Hex::new(1, 0).rotate_cw(), Hex::new(0, 1));
+        assert_eq!(Hex::new(3, -1).rotate_cw().rotate_ccw(), Hex::new(3, -1));
+        assert_eq!(Hex::new(3, -1).rotate_around(&center, 6), Hex::new(3, -1));
+        assert_eq!(Hex::new(3, -1).rotate_around(&center, -3), Hex::new(1, -1));
+        for d in HexDir::ALL {
+            assert_eq!(center.mv(d).mv(d.opposite()), center);
+            assert_eq!(d.offset().rotate_cw(), d.cw().offset());
+        }
+        let cube = Cube::from(center);
+        assert_eq!(cube.q + cube.r + cube.s, 0);
+        assert_eq!(Hex::from(cube), center);
+    }
+
+    #[test]
+    fn follows_parsed_paths() {
+        let walk = |layout: Layout, s: &str| {
+            layout
+                .parse_path(s)
+                .unwrap()
+                .into_iter()
+                .fold(Hex::default(), |h, d| h.mv(d))
+        };
+
+        assert_eq!(walk(Layout::FlatTop, "ne,ne,ne").length(), 3);
+        assert_eq!(walk(Layout::FlatTop, "ne,ne,sw,sw").length(), 0);
+        assert_eq!(walk(Layout::FlatTop, "ne,ne,s,s").length(), 2);
+        assert_eq!(walk(Layout::FlatTop, "se,sw,se,sw,sw").length(), 3);
+        assert_eq!(walk(Layout::PointyTop, "nwwswee"), Hex::default());
+        assert_eq!(walk(Layout::PointyTop, "esew"), Hex::new(0, 1));
+        assert_eq!(Layout::PointyTop.parse_path("n"), None);
+        assert_eq!(
+            Layout::FlatTop
+                .parse_dir("NW")
+                .map(|it| Layout::PointyTop.dir_name(it)),
+            Some("w")
+        );
+    }
+
+    #[test]
+    fn renders_both_layouts() {
+        let cells = Hex::default().spiral(1);
+        let glyphs = cells
+            .iter()
+            .map(|h| (*h, if h.length() == 0 { 'o' } else { '#' }));
+
+        assert_eq!(
+            Layout::PointyTop.ascii(glyphs.clone()).to_string(),
+            " # # \n# o #\n # # "
+        );
+        assert_eq!(
+            Layout::FlatTop.ascii(glyphs).to_string(),
+            " # \n# #\n o \n# #\n # "
+        );
+        for layout in [Layout::PointyTop, Layout::FlatTop] {
+            for p in [Point::new(3, 1), Point::new(-2, 5)] {
+                let h = layout.at_doubled(&p);
+                assert_eq!(h.map(|it| layout.doubled(&it)), h.map(|_| p));
+            }
+            let svg = layout.svg(cells.iter().map(|h| (*h, Colour::Green)));
+            assert_eq!(svg.to_string().matches("<polygon").count(), 7);
+        }
+    }
+}
@@ -0,0 +1,326 @@
+use crate::util::matrix::Matrix;
+use num::rational::Ratio;
+use num::{Signed, Zero};
+
+type Q = Ratio<i64>;
+
+/// Integer linear program: minimise `cost · x` over integer `x` with `0 <= x[i] <= upper[i]`
+/// subject to a set of equations.
+///
+/// The equations are brought into reduced row echelon form over the rationals, which expresses
+/// every pivot variable in terms of the free ones. Only the free variables are then searched,
+/// branch and bound style, so the search space shrinks to the dimension of the null space.
+///
+/// The search needs a finite range for every variable: each one must either get an explicit
+/// `set_upper_bound` or appear with a positive coefficient in an equation whose coefficients are
+/// all non-negative. Equations with mixed signs bound nothing, so `minimize` panics for a
+/// variable that only appears in those.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IntegerProgram {
+    vars: usize,
+    equations: Vec<(Vec<i64>, i64)>,
+    cost: Vec<i64>,
+    upper: Vec<Option<i64>>,
+}
+
+/// Optimal assignment found by `IntegerProgram::minimize`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IlpSolution {
+    pub cost: i64,
+    pub values: Vec<i64>,
+}
+
+impl IntegerProgram {
+    /// Program over `vars` variables, minimising their sum unless told otherwise.
+    pub fn new(vars: usize) -> IntegerProgram {
+        IntegerProgram {
+            vars,
+            equations: vec![],
+            cost: vec![1; vars],
+            upper: vec![None; vars],
+        }
+    }
+
+    /// Adds the equation `coefficients · x = rhs`.
+    pub fn add_equation(&mut self, coefficients: Vec<i64>, rhs: i64) {
+        assert_eq!(
+            coefficients.len(),
+            self.vars,
+            "Wrong number of coefficients"
+        );
+        self.equations.push((coefficients, rhs));
+    }
+
+    pub fn set_cost(&mut self, cost: Vec<i64>) {
+        assert_eq!(cost.len(), self.vars, "Wrong number of costs");
+        self.cost = cost;
+    }
+
+    pub fn set_upper_bound(&mut self, var: usize, bound: i64) {
+        self.upper[var] = Some(bound);
+    }
+
+    /// Upper bound of every variable, either given or implied by an equation without negative
+    /// coefficients. Panics if a variable has neither, see `IntegerProgram`.
+    fn upper_bounds(&self) -> Vec<i64> {
+        (0..self.vars)
+            .map(|var| {
+                self.equations
+                    .iter()
+                    .filter(|(c, _)| c[var] > 0 && c.iter().all(|it| *it >= 0))
+                    .map(|(c, rhs)| rhs.div_euclid(c[var]))
+                    .chain(self.upper[var])
+                    .min()
+                    .unwrap_or_else(|| {
+                        panic!(
+                            "Variable {} is unbounded, give it an upper bound with set_upper_bound",
+                            var
+                        )
+                    })
+            })
+            .collect()
+    }
+
+    /// Reduced row echelon form of the augmented equations, with the pivot column of every
+    /// row, or `None` if they are inconsistent.
+    fn eliminate(&self) -> Option<(Vec<Vec<Q>>, Vec<usize>)> {
+        let mut m = Matrix::from_rows(
+            self.equations
+                .iter()
+                .map(|(c, rhs)| c.iter().chain([rhs]).map(|it| Q::from(*it)).collect())
+                .collect(),
+        );
+        let pivots = m.row_reduce();
+        if pivots.last() == Some(&self.vars) {
+            return None;
+        }
+        let rows = (0..pivots.len()).map(|r| m.row(r).to_vec()).collect();
+        Some((rows, pivots))
+    }
+
+    /// An assignment of minimal cost, or `None` if there is no feasible one.
+    ///
+    /// Panics if a variable has no upper bound, see `IntegerProgram`.
+    pub fn minimize(&self) -> Option<IlpSolution> {
+        let upper = self.upper_bounds();
+        let (rows, pivots) = self.eliminate()?;
+        let free = (0..self.vars)
+            .filter(|it| !pivots.contains(it))
+            .collect::<Vec<_>>();
+        // Substituting the pivot variables turns the cost into `base + reduced · free`
+        let base: Q = rows
+            .iter()
+            .zip(&pivots)
+            .map(|(row, p)| row[self.vars] * self.cost[*p])
+            .sum();
+        let reduced = free
+            .iter()
+            .map(|f| {
+                Q::from(self.cost[*f])
+                    - rows
+                        .iter()
+                        .zip(&pivots)
+                        .map(|(row, p)| row[*f] * self.cost[*p])
+                        .sum::<Q>()
+            })
+            .collect();
+        let mut search = Search {
+            program: self,
+            upper,
+            rows,
+            pivots,
+            free,
+            reduced,
+            values: vec![0; self.vars],
+            best: None,
+        };
+        search.branch(0, base);
+        search.best
+    }
+}
+
+struct Search<'a> {
+    program: &'a IntegerProgram,
+    upper: Vec<i64>,
+    rows: Vec<Vec<Q>>,
+    pivots: Vec<usize>,
+    free: Vec<usize>,
+    reduced: Vec<Q>,
+    values: Vec<i64>,
+    best: Option<IlpSolution>,
+}
+
+impl Search<'_> {
+    /// Assigns the free variables from `depth` on, where `cost` is the substituted cost of the
+    /// ones assigned so far.
+    fn branch(&mut self, depth: usize, cost: Q) {
+        if !self.feasible(depth) || !self.promising(depth, cost) {
+            return;
+        }
+        if depth == self.free.len() {
+            self.complete();
+            return;
+        }
+        let var = self.free[depth];
+        let range = 0..=self.upper[var];
+        let values: Box<dyn Iterator<Item = i64>> = if self.reduced[depth].is_negative() {
+            Box::new(range.rev())
+        } else {
+            Box::new(range)
+        };
+        for v in values {
+            self.values[var] = v;
+            self.branch(depth + 1, cost + self.reduced[depth] * v);
+        }
+        self.values[var] = 0;
+    }
+
+    /// Whether every pivot variable can still end up within its bounds, given that the free
+    /// variables before `depth` are fixed and the rest may take any value in their range.
+    fn feasible(&self, depth: usize) -> bool {
+        let rhs = self.program.vars;
+        self.rows.iter().zip(&self.pivots).all(|(row, p)| {
+            let mut lo = row[rhs];
+            let mut hi = row[rhs];
+            for (i, f) in self.free.iter().enumerate() {
+                if i < depth {
+                    lo -= row[*f] * self.values[*f];
+                    hi -= row[*f] * self.values[*f];
+                } else if row[*f].is_positive() {
+                    lo -= row[*f] * self.upper[*f];
+                } else {
+                    hi -= row[*f] * self.upper[*f];
+                }
+            }
+            hi >= Q::zero() && lo <= Q::from(self.upper[*p])
+        })
+    }
+
+    /// Whether the cheapest completion of the assignment so far could beat the best solution.
+    fn promising(&self, depth: usize, cost: Q) -> bool {
+        let Some(best) = &self.best else {
+            return true;
+        };
+        let bound = cost
+            + self.free[depth..]
+                .iter()
+                .zip(&self.reduced[depth..])
+                .filter(|(_, r)| r.is_negative())
+                .map(|(f, r)| r * self.upper[*f])
+                .sum::<Q>();
+        bound < Q::from(best.cost)
+    }
+
+    fn complete(&mut self) {
+        let rhs = self.program.vars;
+        for (row, p) in self.rows.iter().zip(&self.pivots) {
+            let value = row[rhs]
+                - self
+                    .free
+                    .iter()
+                    .map(|f| row[*f] * self.values[*f])
+                    .sum::<Q>();
+            if !value.is_integer() {
+                return;
+            }
+            self.values[*p] = value.to_integer();
+        }
+        let cost = self
+            .program
+            .cost
+            .iter()
+            .zip(&self.values)
+            .map(|(c, v)| c * v)
+            .sum();
+        if self.best.as_ref().is_none_or(|it| cost < it.cost) {
+            self.best = Some(IlpSolution {
+                cost,
+                values: self.values.clone(),
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use itertools::Itertools;
+
+    type Buttons<'a> = &'a [&'a [usize]];
+
+    fn presses(buttons: Buttons, targets: &[i64]) -> IntegerProgram {
+        let mut program = IntegerProgram::new(buttons.len());
+        for (counter, target) in targets.iter().enumerate() {
+            let coefficients = buttons
+                .iter()
+                .map(|it| i64::from(it.contains(&counter)))
+                .collect();
+            program.add_equation(coefficients, *target);
+        }
+        program
+    }
+
+    #[test]
+    fn solves_the_joltage_examples() {
+        let examples: [(Buttons, &[i64], i64); 3] = [
+            (
+                &[&[3], &[1, 3], &[2], &[2, 3], &[0, 2], &[0, 1]],
+                &[3, 5, 4, 7],
+                10,
+            ),
+            (
+                &[&[0, 2, 3, 4], &[2, 3], &[0, 4], &[0, 1, 2], &[1, 2, 3, 4]],
+                &[7, 5, 12, 7, 2],
+                12,
+            ),
+            (
+                &[&[0, 1, 2, 3, 4], &[0, 3, 4], &[0, 1, 2, 4, 5], &[1, 2]],
+                &[10, 11, 11, 5, 10, 5],
+                11,
+            ),
+        ];
+        for (buttons, targets, expected) in examples {
+            let solution = presses(buttons, targets).minimize().unwrap();
+            assert_eq!(solution.cost, expected);
+            for (counter, target) in targets.iter().enumerate() {
+                let total: i64 = (0..buttons.len())
+                    .filter(|b| buttons[*b].contains(&counter))
+                    .map(|b| solution.values[b])
+                    .sum();
+                assert_eq!(total, *target);
+            }
+        }
+        assert_eq!(presses(&[&[0, 1]], &[1, 2]).minimize(), None);
+    }
+
+    #[test]
+    fn matches_brute_force_with_mixed_signs() {
+        for seed in 0..40i64 {
+            let coefficient = |i: i64| (seed * 7 + i * 13) % 5 - 1;
+            let mut program = IntegerProgram::new(4);
+            for var in 0..4 {
+                program.set_upper_bound(var, 3 + (seed + var as i64) % 3);
+            }
+            program.set_cost((0..4).map(|i| (seed + i * 3) % 7 - 2).collect());
+            for row in 0..2 {
+                let c = (0..4).map(|i| coefficient(row * 4 + i)).collect_vec();
+                program.add_equation(c, seed % 6 + row);
+            }
+
+            let brute = program
+                .upper_bounds()
+                .iter()
+                .map(|u| 0..=*u)
+                .multi_cartesian_product()
+                .filter(|x| {
+                    program
+                        .equations
+                        .iter()
+                        .all(|(c, rhs)| c.iter().zip(x).map(|(a, b)| a * b).sum::<i64>() == *rhs)
+                })
+                .map(|x| program.cost.iter().zip(&x).map(|(a, b)| a * b).sum::<i64>())
+                .min();
+            assert_eq!(program.minimize().map(|it| it.cost), brute, "{}", seed);
+        }
+    }
+}
@@ -0,0 +1,427 @@
+use num::PrimInt;
+use prse::{Parse, try_parse};
+use std::collections::BTreeMap;
+use std::fmt::{Debug, Display, Formatter};
+use std::str::FromStr;
+
+/// Inclusive range of integers `min..=max`; empty if `max < min`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, Ord, PartialOrd)]
+pub struct Interval<T> {
+    pub min: T,
+    pub max: T,
+}
+
+impl<T: Display> Display for Interval<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}-{}", self.min, self.max)
+    }
+}
+
+impl<T: for<'a> Parse<'a>> FromStr for Interval<T> {
+    type Err = prse::ParseError;
+
+    /// Parses `min-max`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (min, max) = try_parse!(s.trim(), "{}-{}")?;
+        Ok(Interval { min, max })
+    }
+}
+
+impl<T: PrimInt> Interval<T> {
+    pub fn new(min: T, max: T) -> Interval<T> {
+        Interval { min, max }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.max < self.min
+    }
+
+    /// Number of integers in the interval.
+    pub fn len(&self) -> T {
+        if self.is_empty() {
+            T::zero()
+        } else {
+            self.max - self.min + T::one()
+        }
+    }
+
+    pub fn contains(&self, x: T) -> bool {
+        self.min <= x && x <= self.max
+    }
+
+    pub fn overlaps(&self, other: &Interval<T>) -> bool {
+        self.intersect(other).is_some()
+    }
+
+    pub fn intersect(&self, other: &Interval<T>) -> Option<Interval<T>> {
+        let i = Interval::new(self.min.max(other.min), self.max.min(other.max));
+        (!i.is_empty()).then_some(i)
+    }
+}
+
+/// Set of integers stored as sorted, disjoint intervals.
+///
+/// Overlapping and adjacent intervals are merged on insert, so every point lies in exactly one
+/// stored interval and lookups take O(log n).
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub struct IntervalSet<T> {
+    intervals: BTreeMap<T, T>,
+}
+
+impl<T> Default for IntervalSet<T> {
+    fn default() -> Self {
+        IntervalSet {
+            intervals: BTreeMap::new(),
+        }
+    }
+}
+
+impl<T: PrimInt> FromIterator<Interval<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = Interval<T>>>(iter: I) -> Self {
+        let mut set = IntervalSet::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl<T: PrimInt> Extend<Interval<T>> for IntervalSet<T> {
+    fn extend<I: IntoIterator<Item = Interval<T>>>(&mut self, iter: I) {
+        for interval in iter {
+            self.insert(interval);
+        }
+    }
+}
+
+impl<T: PrimInt> IntervalSet<T> {
+    pub fn new() -> IntervalSet<T> {
+        IntervalSet::default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    /// Number of disjoint intervals.
+    pub fn interval_count(&self) -> usize {
+        self.intervals.len()
+    }
+
+    /// Number of integers in the set.
+    pub fn total_len(&self) -> T {
+        self.iter().fold(T::zero(), |acc, it| acc + it.len())
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Interval<T>> + '_ {
+        self.intervals
+            .iter()
+            .map(|(min, max)| Interval::new(*min, *max))
+    }
+
+    /// The stored interval containing `x`, if any.
+    pub fn interval_containing(&self, x: T) -> Option<Interval<T>> {
+        let (min, max) = self.intervals.range(..=x).next_back()?;
+        (x <= *max).then(|| Interval::new(*min, *max))
+    }
+
+    pub fn contains(&self, x: T) -> bool {
+        self.interval_containing(x).is_some()
+    }
+
+    /// Adds all integers of `interval`, merging it with any overlapping or adjacent intervals.
+    pub fn insert(&mut self, interval: Interval<T>) {
+        if interval.is_empty() {
+            return;
+        }
+        let Interval { mut min, mut max } = interval;
+        // An interval ending right before `min` is adjacent and gets merged as well
+        let from = min.checked_sub(&T::one()).unwrap_or(min);
+        if let Some((m, x)) = self.intervals.range(..=from).next_back()
+            && *x >= from
+        {
+            min = *m;
+            max = max.max(*x);
+        }
+        let to = max.checked_add(&T::one()).unwrap_or(max);
+        let merged = self
+            .intervals
+            .range(min..=to)
+            .map(|(m, x)| (*m, *x))
+            .collect::<Vec<_>>();
+        for (m, x) in merged {
+            self.intervals.remove(&m);
+            max = max.max(x);
+        }
+        self.intervals.insert(min, max);
+    }
+
+    /// Removes all integers of `interval`, splitting stored intervals where necessary.
+    pub fn remove(&mut self, interval: Interval<T>) {
+        if interval.is_empty() {
+            return;
+        }
+        let overlapping = self
+            .intervals
+            .range(..=interval.max)
+            .rev()
+            .take_while(|(_, x)| **x >= interval.min)
+            .map(|(m, x)| (*m, *x))
+            .collect::<Vec<_>>();
+        for (m, x) in overlapping {
+            self.intervals.remove(&m);
+            if m < interval.min {
+                self.intervals.insert(m, interval.min - T::one());
+            }
+            if x > interval.max {
+                self.intervals.insert(interval.max + T::one(), x);
+            }
+        }
+    }
+
+    pub fn union(&self, other: &IntervalSet<T>) -> IntervalSet<T> {
+        let mut result = self.clone();
+        result.extend(other.iter());
+        result
+    }
+
+    pub fn intersection(&self, other: &IntervalSet<T>) -> IntervalSet<T> {
+        let mut result = IntervalSet::new();
+        let (mut a, mut b) = (self.iter().peekable(), other.iter().peekable());
+        while let (Some(x), Some(y)) = (a.peek(), b.peek()) {
+            if let Some(i) = x.intersect(y) {
+                result.intervals.insert(i.min, i.max);
+            }
+            if x.max < y.max {
+                a.next();
+            } else {
+                b.next();
+            }
+        }
+        result
+    }
+
+    pub fn difference(&self, other: &IntervalSet<T>) -> IntervalSet<T> {
+        let mut result = self.clone();
+        for interval in other.iter() {
+            result.remove(interval);
+        }
+        result
+    }
+
+    /// All integers of `within` that are not in the set.
+    pub fn complement(&self, within: Interval<T>) -> IntervalSet<T> {
+        let mut result = IntervalSet::new();
+        result.insert(within);
+        result.difference(self)
+    }
+}
+
+/// Static collection of possibly overlapping intervals for stabbing and overlap queries.
+///
+/// Queries return indices into the intervals as they were given to `new`, in ascending order.
+#[derive(Debug, Clone)]
+pub struct IntervalTree<T> {
+    /// Intervals sorted by `min`, together with their original index.
+    nodes: Vec<(Interval<T>, usize)>,
+    /// Largest `max` in the implicit subtree rooted at each node.
+    max_end: Vec<T>,
+    mins: Vec<T>,
+    maxs: Vec<T>,
+}
+
+impl<T: PrimInt> FromIterator<Interval<T>> for IntervalTree<T> {
+    fn from_iter<I: IntoIterator<Item = Interval<T>>>(iter: I) -> Self {
+        IntervalTree::new(&iter.into_iter().collect::<Vec<_>>())
+    }
+}
+
+impl<T: PrimInt> IntervalTree<T> {
+    /// Builds the tree in O(n log n). Empty intervals are kept but never match.
+    pub fn new(intervals: &[Interval<T>]) -> IntervalTree<T> {
+        let mut nodes = intervals.iter().copied().zip(0..).collect::<Vec<_>>();
+        nodes.sort_by_key(|(it, _)| it.min);
+        let mut tree = IntervalTree {
+            max_end: nodes.iter().map(|(it, _)| it.max).collect(),
+            mins: nodes
+                .iter()
+                .filter(|(it, _)| !it.is_empty())
+                .map(|(it, _)| it.min)
+                .collect(),
+            maxs: nodes
+                .iter()
+                .filter(|(it, _)| !it.is_empty())
+                .map(|(it, _)| it.max)
+                .collect(),
+            nodes,
+        };
+        tree.maxs.sort();
+        tree.build(0, tree.nodes.len());
+        tree
+    }
+
+    fn build(&mut self, lo: usize, hi: usize) -> Option<T> {
+        if lo >= hi {
+            return None;
+        }
+        let mid = (lo + hi) / 2;
+        let left = self.build(lo, mid);
+        let right = self.build(mid + 1, hi);
+        let max = [left, right]
+            .into_iter()
+            .flatten()
+            .fold(self.max_end[mid], T::max);
+        self.max_end[mid] = max;
+        Some(max)
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Indices of all intervals overlapping `query`, in O(min(n, (k + 1) log n)) for `k` matches:
+    /// the `max_end` pruning can cost a root-to-leaf path per match, not a single one overall.
+    pub fn overlapping(&self, query: Interval<T>) -> Vec<usize> {
+        let mut result = vec![];
+        if !query.is_empty() {
+            self.collect(0, self.nodes.len(), &query, &mut result);
+        }
+        result.sort_unstable();
+        result
+    }
+
+    fn collect(&self, lo: usize, hi: usize, query: &Interval<T>, result: &mut Vec<usize>) {
+        if lo >= hi {
+            return;
+        }
+        let mid = (lo + hi) / 2;
+        if self.max_end[mid] < query.min {
+            return;
+        }
+        self.collect(lo, mid, query, result);
+        let (interval, index) = &self.nodes[mid];
+        // Everything right of `mid` starts at least as late
+        if interval.min > query.max {
+            return;
+        }
+        if interval.overlaps(query) {
+            result.push(*index);
+        }
+        self.collect(mid + 1, hi, query, result);
+    }
+
+    /// Indices of all intervals containing `x`, with the same cost as `overlapping`.
+    pub fn containing(&self, x: T) -> Vec<usize> {
+        self.overlapping(Interval::new(x, x))
+    }
+
+    /// Number of intervals containing `x`, in O(log n): all that start at or before `x` minus
+    /// those that also end before it.
+    pub fn count_containing(&self, x: T) -> usize {
+        self.mins.partition_point(|it| *it <= x) - self.maxs.partition_point(|it| *it < x)
+    }
+
+    /// `count_containing` for every point of the sorted slice `points`, in a single sweep over
+    /// the points and interval ends together.
+    pub fn count_containing_sorted(&self, points: &[T]) -> Vec<usize> {
+        assert!(points.is_sorted(), "Points must be sorted");
+        let (mut started, mut ended) = (0, 0);
+        points
+            .iter()
+            .map(|x| {
+                while started < self.mins.len() && self.mins[started] <= *x {
+                    started += 1;
+                }
+                while ended < self.maxs.len() && self.maxs[ended] < *x {
+                    ended += 1;
+                }
+                started - ended
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(intervals: &[(i32, i32)]) -> IntervalSet<i32> {
+        intervals
+            .iter()
+            .map(|(min, max)| Interval::new(*min, *max))
+            .collect()
+    }
+
+    fn intervals(set: &IntervalSet<i32>) -> Vec<(i32, i32)> {
+        set.iter().map(|it| (it.min, it.max)).collect()
+    }
+
+    #[test]
+    fn merges_overlapping_and_adjacent_intervals() {
+        let ranges: Vec<Interval<u64>> = ["3-5", "10-14", "16-20", "12-18"]
+            .iter()
+            .map(|it| it.parse().unwrap())
+            .collect();
+        let fresh: IntervalSet<u64> = ranges.into_iter().collect();
+
+        assert_eq!(fresh.total_len(), 14);
+        assert_eq!(fresh.interval_count(), 2);
+        assert_eq!(
+            [1, 5, 8, 11, 17, 32].map(|it| fresh.contains(it)),
+            [false, true, false, true, true, false]
+        );
+        assert_eq!(fresh.interval_containing(17), Some(Interval::new(10, 20)));
+        assert_eq!(
+            intervals(&set(&[(1, 2), (3, 4), (6, 6)])),
+            vec![(1, 4), (6, 6)]
+        );
+        assert_eq!(intervals(&set(&[(5, 9), (1, 2), (0, 20)])), vec![(0, 20)]);
+        assert_eq!(
+            intervals(&set(&[(i32::MIN, 0), (i32::MAX, i32::MAX)])),
+            vec![(i32::MIN, 0), (i32::MAX, i32::MAX)]
+        );
+    }
+
+    #[test]
+    fn removes_and_combines_sets() {
+        let mut a = set(&[(0, 10), (20, 30)]);
+        a.remove(Interval::new(5, 22));
+        assert_eq!(intervals(&a), vec![(0, 4), (23, 30)]);
+        a.remove(Interval::new(2, 2));
+        assert_eq!(intervals(&a), vec![(0, 1), (3, 4), (23, 30)]);
+
+        let b = set(&[(1, 3), (25, 40)]);
+        assert_eq!(
+            intervals(&a.intersection(&b)),
+            vec![(1, 1), (3, 3), (25, 30)]
+        );
+        assert_eq!(intervals(&a.union(&b)), vec![(0, 4), (23, 40)]);
+        assert_eq!(intervals(&a.difference(&b)), vec![(0, 0), (4, 4), (23, 24)]);
+        assert_eq!(
+            intervals(&a.complement(Interval::new(-5, 25))),
+            vec![(-5, -1), (2, 2), (5, 22)]
+        );
+    }
+
+    #[test]
+    fn answers_stabbing_queries_on_overlapping_intervals() {
+        let ranges = [(3, 5), (10, 14), (16, 20), (12, 18), (7, 6)]
+            .map(|(min, max)| Interval::new(min, max));
+        let tree = IntervalTree::new(&ranges);
+
+        assert_eq!(tree.containing(13), vec![1, 3]);
+        assert_eq!(tree.containing(6), vec![]);
+        assert_eq!(tree.overlapping(Interval::new(5, 10)), vec![0, 1]);
+        assert_eq!(tree.overlapping(Interval::new(0, 100)), vec![0, 1, 2, 3]);
+        let points = (0..25).collect::<Vec<_>>();
+        let counts = tree.count_containing_sorted(&points);
+        for x in points {
+            let brute = ranges.iter().filter(|it| it.contains(x)).count();
+            assert_eq!(tree.count_containing(x), brute);
+            assert_eq!(tree.containing(x).len(), brute);
+            assert_eq!(counts[x as usize], brute);
+        }
+    }
+}
@@ -0,0 +1,280 @@
+use crate::util::graph::Edge;
+use crate::util::point3::Point3;
+use crate::util::{Coord, Point};
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+/// Point with a fixed number of integer coordinates, for use in a `KdTree`.
+pub trait KdPoint: Copy {
+    const DIM: usize;
+
+    fn coord(&self, axis: usize) -> i64;
+
+    /// Squared Euclidean distance.
+    fn dist_sq(&self, other: &Self) -> i64 {
+        (0..Self::DIM)
+            .map(|axis| (self.coord(axis) - other.coord(axis)).pow(2))
+            .sum()
+    }
+}
+
+impl<T: Coord> KdPoint for Point3<T> {
+    const DIM: usize = 3;
+
+    fn coord(&self, axis: usize) -> i64 {
+        [self.x, self.y, self.z][axis].to_i64().unwrap()
+    }
+}
+
+impl<T: Coord> KdPoint for Point<T> {
+    const DIM: usize = 2;
+
+    fn coord(&self, axis: usize) -> i64 {
+        [self.x, self.y][axis].to_i64().unwrap()
+    }
+}
+
+/// Static k-d tree for nearest neighbour and radius queries.
+///
+/// Queries return indices into the points as they were given to `new`. Ties in distance are
+/// broken by index, so results are deterministic.
+#[derive(Debug, Clone)]
+pub struct KdTree<P> {
+    /// Implicit tree: the node of `lo..hi` is at `(lo + hi) / 2`, splitting on `depth % DIM`.
+    nodes: Vec<(P, usize)>,
+}
+
+impl<P: KdPoint> KdTree<P> {
+    pub fn new(points: &[P]) -> KdTree<P> {
+        let mut nodes = points.iter().copied().zip(0..).collect::<Vec<_>>();
+        build(&mut nodes, 0);
+        KdTree { nodes }
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// The `k` points closest to `q` as `(index, squared distance)`, closest first.
+    pub fn nearest(&self, q: &P, k: usize) -> Vec<(usize, i64)> {
+        let mut best = BinaryHeap::new();
+        if k > 0 {
+            self.nearest_in(0, self.nodes.len(), 0, q, k, &mut best);
+        }
+        let mut result = best.into_iter().map(|(d, i)| (i, d)).collect::<Vec<_>>();
+        result.sort_by_key(|(i, d)| (*d, *i));
+        result
+    }
+
+    fn nearest_in(
+        &self,
+        lo: usize,
+        hi: usize,
+        depth: usize,
+        q: &P,
+        k: usize,
+        best: &mut BinaryHeap<(i64, usize)>,
+    ) {
+        if lo >= hi {
+            return;
+        }
+        let mid = (lo + hi) / 2;
+        let (p, index) = &self.nodes[mid];
+        let candidate = (q.dist_sq(p), *index);
+        if best.len() < k {
+            best.push(candidate);
+        } else if candidate < *best.peek().unwrap() {
+            best.pop();
+            best.push(candidate);
+        }
+
+        let axis = depth % P::DIM;
+        let diff = q.coord(axis) - p.coord(axis);
+        let (near, far) = if diff < 0 {
+            ((lo, mid), (mid + 1, hi))
+        } else {
+            ((mid + 1, hi), (lo, mid))
+        };
+        self.nearest_in(near.0, near.1, depth + 1, q, k, best);
+        if best.len() < k || diff * diff <= best.peek().unwrap().0 {
+            self.nearest_in(far.0, far.1, depth + 1, q, k, best);
+        }
+    }
+
+    /// Indices of all points with a squared distance of at most `radius_sq` from `q`, ascending.
+    pub fn within(&self, q: &P, radius_sq: i64) -> Vec<usize> {
+        let mut result = vec![];
+        self.within_in(0, self.nodes.len(), 0, q, radius_sq, &mut result);
+        result.sort_unstable();
+        result
+    }
+
+    fn within_in(
+        &self,
+        lo: usize,
+        hi: usize,
+        depth: usize,
+        q: &P,
+        radius_sq: i64,
+        result: &mut Vec<usize>,
+    ) {
+        if lo >= hi {
+            return;
+        }
+        let mid = (lo + hi) / 2;
+        let (p, index) = &self.nodes[mid];
+        if q.dist_sq(p) <= radius_sq {
+            result.push(*index);
+        }
+        let axis = depth % P::DIM;
+        let diff = q.coord(axis) - p.coord(axis);
+        if diff <= 0 || diff * diff <= radius_sq {
+            self.within_in(lo, mid, depth + 1, q, radius_sq, result);
+        }
+        if diff >= 0 || diff * diff <= radius_sq {
+            self.within_in(mid + 1, hi, depth + 1, q, radius_sq, result);
+        }
+    }
+
+    /// All pairs `a < b` as edges weighted by squared distance, in increasing order, ties broken
+    /// by `(a, b)`.
+    ///
+    /// Pairs are found lazily through nearest neighbour queries, so taking the first `m` pairs
+    /// needs memory for about `n + m` of them instead of all `n²`.
+    pub fn pairs_by_distance(&self) -> ClosestPairs<'_, P> {
+        let mut pairs = ClosestPairs {
+            tree: self,
+            points: self.points(),
+            neighbours: vec![vec![]; self.len()],
+            fetched: vec![0; self.len()],
+            heap: BinaryHeap::new(),
+        };
+        for a in 0..self.len() {
+            pairs.push_next(a);
+        }
+        pairs
+    }
+
+    fn points(&self) -> Vec<P> {
+        let mut points = self.nodes.clone();
+        points.sort_unstable_by_key(|(_, i)| *i);
+        points.into_iter().map(|(p, _)| p).collect()
+    }
+}
+
+/// Sorts `nodes` into an implicit k-d tree.
+fn build<P: KdPoint>(nodes: &mut [(P, usize)], depth: usize) {
+    if nodes.len() <= 1 {
+        return;
+    }
+    let mid = nodes.len() / 2;
+    let axis = depth % P::DIM;
+    nodes.select_nth_unstable_by_key(mid, |(p, _)| p.coord(axis));
+    let (left, right) = nodes.split_at_mut(mid);
+    build(left, depth + 1);
+    build(&mut right[1..], depth + 1);
+}
+
+/// Iterator returned by `KdTree::pairs_by_distance`.
+pub struct ClosestPairs<'a, P> {
+    tree: &'a KdTree<P>,
+    points: Vec<P>,
+    /// Remaining fetched neighbours `b > a` of every point `a`, furthest first.
+    neighbours: Vec<Vec<(i64, usize)>>,
+    /// How many nearest neighbours of every point have been fetched so far.
+    fetched: Vec<usize>,
+    heap: BinaryHeap<Reverse<(i64, usize, usize)>>,
+}
+
+impl<P: KdPoint> ClosestPairs<'_, P> {
+    /// Queues the next closest partner of `a`, fetching twice as many neighbours when needed.
+    fn push_next(&mut self, a: usize) {
+        while self.neighbours[a].is_empty() && self.fetched[a] < self.tree.len() {
+            let k = (self.fetched[a] * 2).max(8).min(self.tree.len());
+            let nearest = self.tree.nearest(&self.points[a], k);
+            self.neighbours[a] = nearest[self.fetched[a]..]
+                .iter()
+                .rev()
+                .filter(|(b, _)| *b > a)
+                .map(|(b, d)| (*d, *b))
+                .collect();
+            self.fetched[a] = k;
+        }
+        if let Some((d, b)) = self.neighbours[a].pop() {
+            self.heap.push(Reverse((d, a, b)));
+        }
+    }
+}
+
+impl<P: KdPoint> Iterator for ClosestPairs<'_, P> {
+    type Item = Edge<i64>;
+
+    fn next(&mut self) -> Option<Edge<i64>> {
+        let Reverse((d, a, b)) = self.heap.pop()?;
+        self.push_next(a);
+        Some(Edge::new(a, b, d))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use itertools::Itertools;
+
+    fn points() -> Vec<Point3> {
+        (0..150i64)
+            .map(|i| Point3::new(i * 37 % 101, i * 53 % 97, i * 11 % 13))
+            .collect()
+    }
+
+    #[test]
+    fn answers_nearest_and_radius_queries() {
+        let points = points();
+        let tree = KdTree::new(&points);
+        let q = Point3::new(50, 40, 6);
+        let by_distance = (0..points.len())
+            .map(|i| (i, q.dist_sq(&points[i])))
+            .sorted_by_key(|(i, d)| (*d, *i))
+            .collect_vec();
+
+        assert_eq!(tree.nearest(&q, 10), by_distance[..10]);
+        assert_eq!(tree.nearest(&q, 1000), by_distance);
+        assert_eq!(
+            tree.within(&q, 400),
+            by_distance
+                .iter()
+                .filter(|(_, d)| *d <= 400)
+                .map(|(i, _)| *i)
+                .sorted()
+                .collect_vec()
+        );
+    }
+
+    #[test]
+    fn lists_pairs_in_increasing_distance() {
+        let points = points();
+        let expected = (0..points.len())
+            .tuple_combinations()
+            .map(|(a, b)| Edge::new(a, b, points[a].dist_sq(&points[b])))
+            .sorted_by_key(|it| (it.weight, it.a, it.b))
+            .collect_vec();
+
+        let tree = KdTree::new(&points);
+        assert_eq!(
+            tree.pairs_by_distance().take(500).collect_vec(),
+            expected[..500]
+        );
+        assert_eq!(tree.pairs_by_distance().count(), expected.len());
+
+        let grid = [Point::new(0, 0), Point::new(3, 4), Point::new(1, 1)];
+        let pairs = KdTree::new(&grid).pairs_by_distance().collect_vec();
+        assert_eq!(
+            pairs,
+            vec![Edge::new(0, 2, 2), Edge::new(1, 2, 13), Edge::new(0, 1, 25)]
+        );
+    }
+}
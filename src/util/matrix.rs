@@ -0,0 +1,604 @@
+use itertools::iproduct;
+use num::bigint::BigInt;
+use num::rational::{BigRational, Ratio};
+use num::{Integer, Num, One, Signed, Zero};
+use std::fmt::{Display, Formatter};
+use std::ops::{Index, IndexMut, Mul};
+
+pub type Rational = BigRational;
+
+/// Dense row-major matrix with exact entries, e.g. `Matrix<Rational>` or `Matrix<BigInt>`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Matrix<T> {
+    rows: usize,
+    cols: usize,
+    data: Vec<T>,
+}
+
+/// All solutions `particular + Σ kᵢ · null_space[i]` of a linear system.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AffineSolution<T> {
+    pub particular: Vec<T>,
+    pub null_space: Vec<Vec<T>>,
+}
+
+/// Hermite normal form `h = u · a` of an integer matrix `a`, with `u` unimodular.
+///
+/// `h` is in row echelon form with positive pivots and the entries above every pivot reduced
+/// to `0..pivot`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HermiteForm {
+    pub h: Matrix<BigInt>,
+    pub u: Matrix<BigInt>,
+    /// Pivot column of every non-zero row of `h`.
+    pub pivots: Vec<usize>,
+}
+
+/// Smith normal form `d = u · a · v` of an integer matrix `a`, with `u` and `v` unimodular.
+///
+/// `d` is diagonal, its non-negative diagonal entries each divide the next and the non-zero
+/// ones come first.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SmithForm {
+    pub d: Matrix<BigInt>,
+    pub u: Matrix<BigInt>,
+    pub v: Matrix<BigInt>,
+}
+
+impl<T: Clone + Num> AffineSolution<T> {
+    /// Number of free parameters.
+    pub fn dimension(&self) -> usize {
+        self.null_space.len()
+    }
+
+    /// The solution for the given free parameters.
+    pub fn at(&self, params: &[T]) -> Vec<T> {
+        assert_eq!(params.len(), self.dimension(), "Wrong number of parameters");
+        let mut x = self.particular.clone();
+        for (k, v) in params.iter().zip(&self.null_space) {
+            for (xi, vi) in x.iter_mut().zip(v) {
+                *xi = xi.clone() + k.clone() * vi.clone();
+            }
+        }
+        x
+    }
+}
+
+impl<T> Matrix<T> {
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn is_square(&self) -> bool {
+        self.rows == self.cols
+    }
+
+    pub fn row(&self, r: usize) -> &[T] {
+        &self.data[r * self.cols..(r + 1) * self.cols]
+    }
+
+    pub fn swap_rows(&mut self, a: usize, b: usize) {
+        if a != b {
+            for c in 0..self.cols {
+                self.data.swap(a * self.cols + c, b * self.cols + c);
+            }
+        }
+    }
+
+    pub fn swap_cols(&mut self, a: usize, b: usize) {
+        if a != b {
+            for r in 0..self.rows {
+                self.data.swap(r * self.cols + a, r * self.cols + b);
+            }
+        }
+    }
+}
+
+impl<T: Clone> Matrix<T> {
+    pub fn from_fn(rows: usize, cols: usize, mut f: impl FnMut(usize, usize) -> T) -> Matrix<T> {
+        let data = iproduct!(0..rows, 0..cols).map(|(r, c)| f(r, c)).collect();
+        Matrix { rows, cols, data }
+    }
+
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Matrix<T> {
+        let cols = rows.first().map_or(0, Vec::len);
+        assert!(
+            rows.iter().all(|it| it.len() == cols),
+            "Rows differ in length"
+        );
+        Matrix {
+            rows: rows.len(),
+            cols,
+            data: rows.into_iter().flatten().collect(),
+        }
+    }
+
+    pub fn column(&self, c: usize) -> Vec<T> {
+        (0..self.rows).map(|r| self[(r, c)].clone()).collect()
+    }
+
+    pub fn transpose(&self) -> Matrix<T> {
+        Matrix::from_fn(self.cols, self.rows, |r, c| self[(c, r)].clone())
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Matrix<U> {
+        Matrix {
+            rows: self.rows,
+            cols: self.cols,
+            data: self.data.iter().map(f).collect(),
+        }
+    }
+
+    /// Appends the columns of `other`, e.g. a right hand side.
+    pub fn augment(&self, other: &Matrix<T>) -> Matrix<T> {
+        assert_eq!(self.rows, other.rows, "Row counts differ");
+        Matrix::from_fn(self.rows, self.cols + other.cols, |r, c| {
+            if c < self.cols {
+                self[(r, c)].clone()
+            } else {
+                other[(r, c - self.cols)].clone()
+            }
+        })
+    }
+}
+
+impl<T: Clone + Num> Matrix<T> {
+    pub fn zeros(rows: usize, cols: usize) -> Matrix<T> {
+        Matrix::from_fn(rows, cols, |_, _| T::zero())
+    }
+
+    pub fn identity(n: usize) -> Matrix<T> {
+        Matrix::from_fn(n, n, |r, c| if r == c { T::one() } else { T::zero() })
+    }
+
+    pub fn mul_vec(&self, v: &[T]) -> Vec<T> {
+        assert_eq!(v.len(), self.cols, "Dimension mismatch");
+        (0..self.rows)
+            .map(|r| {
+                self.row(r)
+                    .iter()
+                    .zip(v)
+                    .fold(T::zero(), |acc, (a, b)| acc + a.clone() * b.clone())
+            })
+            .collect()
+    }
+
+    pub fn scale_row(&mut self, r: usize, factor: &T) {
+        for c in 0..self.cols {
+            self[(r, c)] = self[(r, c)].clone() * factor.clone();
+        }
+    }
+
+    /// Subtracts `factor` times row `source` from row `target`.
+    pub fn sub_row_multiple(&mut self, target: usize, source: usize, factor: &T) {
+        for c in 0..self.cols {
+            let v = self[(source, c)].clone() * factor.clone();
+            self[(target, c)] = self[(target, c)].clone() - v;
+        }
+    }
+
+    /// Subtracts `factor` times column `source` from column `target`.
+    pub fn sub_col_multiple(&mut self, target: usize, source: usize, factor: &T) {
+        for r in 0..self.rows {
+            let v = self[(r, source)].clone() * factor.clone();
+            self[(r, target)] = self[(r, target)].clone() - v;
+        }
+    }
+}
+
+impl<T> Index<(usize, usize)> for Matrix<T> {
+    type Output = T;
+
+    fn index(&self, (r, c): (usize, usize)) -> &T {
+        assert!(
+            r < self.rows && c < self.cols,
+            "Index ({}, {}) out of range",
+            r,
+            c
+        );
+        &self.data[r * self.cols + c]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Matrix<T> {
+    fn index_mut(&mut self, (r, c): (usize, usize)) -> &mut T {
+        assert!(
+            r < self.rows && c < self.cols,
+            "Index ({}, {}) out of range",
+            r,
+            c
+        );
+        &mut self.data[r * self.cols + c]
+    }
+}
+
+impl<T: Clone + Num> Mul for &Matrix<T> {
+    type Output = Matrix<T>;
+
+    fn mul(self, rhs: &Matrix<T>) -> Matrix<T> {
+        assert_eq!(self.cols, rhs.rows, "Dimension mismatch");
+        Matrix::from_fn(self.rows, rhs.cols, |r, c| {
+            (0..self.cols).fold(T::zero(), |acc, k| {
+                acc + self[(r, k)].clone() * rhs[(k, c)].clone()
+            })
+        })
+    }
+}
+
+impl<T: Display> Display for Matrix<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for r in 0..self.rows {
+            if r > 0 {
+                writeln!(f)?;
+            }
+            for (c, v) in self.row(r).iter().enumerate() {
+                if c > 0 {
+                    write!(f, " ")?;
+                }
+                write!(f, "{}", v)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<T: Clone + Integer + Signed> Matrix<Ratio<T>> {
+    /// Brings the matrix into reduced row echelon form in place and returns the pivot column of
+    /// every non-zero row.
+    pub fn row_reduce(&mut self) -> Vec<usize> {
+        let mut pivots = vec![];
+        for col in 0..self.cols {
+            let rank = pivots.len();
+            let Some(pivot) = (rank..self.rows).find(|r| !self[(*r, col)].is_zero()) else {
+                continue;
+            };
+            self.swap_rows(rank, pivot);
+            let lead = self[(rank, col)].recip();
+            self.scale_row(rank, &lead);
+            for r in 0..self.rows {
+                let factor = self[(r, col)].clone();
+                if r != rank && !factor.is_zero() {
+                    self.sub_row_multiple(r, rank, &factor);
+                }
+            }
+            pivots.push(col);
+        }
+        pivots
+    }
+
+    pub fn rref(&self) -> (Matrix<Ratio<T>>, Vec<usize>) {
+        let mut m = self.clone();
+        let pivots = m.row_reduce();
+        (m, pivots)
+    }
+
+    pub fn rank(&self) -> usize {
+        self.rref().1.len()
+    }
+
+    pub fn determinant(&self) -> Ratio<T> {
+        assert!(self.is_square(), "Determinant of a non-square matrix");
+        let mut m = self.clone();
+        let mut det = Ratio::one();
+        for col in 0..self.cols {
+            let Some(pivot) = (col..self.rows).find(|r| !m[(*r, col)].is_zero()) else {
+                return Ratio::zero();
+            };
+            if pivot != col {
+                m.swap_rows(col, pivot);
+                det = -det;
+            }
+            det = det * m[(col, col)].clone();
+            for r in col + 1..self.rows {
+                let factor = m[(r, col)].clone() / m[(col, col)].clone();
+                m.sub_row_multiple(r, col, &factor);
+            }
+        }
+        det
+    }
+
+    /// `None` for singular matrices.
+    pub fn inverse(&self) -> Option<Matrix<Ratio<T>>> {
+        assert!(self.is_square(), "Inverse of a non-square matrix");
+        let n = self.rows;
+        let (m, pivots) = self.augment(&Matrix::identity(n)).rref();
+        // Invertible exactly if the left half reduced to the identity
+        if pivots.into_iter().ne(0..n) {
+            return None;
+        }
+        Some(Matrix::from_fn(n, n, |r, c| m[(r, n + c)].clone()))
+    }
+
+    /// All solutions of `self · x = b`, or `None` if there are none.
+    pub fn solve(&self, b: &[Ratio<T>]) -> Option<AffineSolution<Ratio<T>>> {
+        assert_eq!(b.len(), self.rows, "Dimension mismatch");
+        let rhs = Matrix::from_fn(self.rows, 1, |r, _| b[r].clone());
+        let (m, pivots) = self.augment(&rhs).rref();
+        if pivots.last() == Some(&self.cols) {
+            return None;
+        }
+        let mut particular = vec![Ratio::zero(); self.cols];
+        for (r, p) in pivots.iter().enumerate() {
+            particular[*p] = m[(r, self.cols)].clone();
+        }
+        let null_space = (0..self.cols)
+            .filter(|it| !pivots.contains(it))
+            .map(|free| {
+                let mut v = vec![Ratio::zero(); self.cols];
+                v[free] = Ratio::one();
+                for (r, p) in pivots.iter().enumerate() {
+                    v[*p] = -m[(r, free)].clone();
+                }
+                v
+            })
+            .collect();
+        Some(AffineSolution {
+            particular,
+            null_space,
+        })
+    }
+
+    pub fn null_space(&self) -> Vec<Vec<Ratio<T>>> {
+        self.solve(&vec![Ratio::zero(); self.rows])
+            .unwrap()
+            .null_space
+    }
+}
+
+impl Matrix<BigInt> {
+    pub fn to_rational(&self) -> Matrix<Rational> {
+        self.map(|it| Rational::from_integer(it.clone()))
+    }
+
+    pub fn rank(&self) -> usize {
+        self.to_rational().rank()
+    }
+
+    /// Determinant by fraction free Bareiss elimination.
+    pub fn determinant(&self) -> BigInt {
+        assert!(self.is_square(), "Determinant of a non-square matrix");
+        let n = self.rows;
+        if n == 0 {
+            return BigInt::one();
+        }
+        let mut m = self.clone();
+        let mut sign = BigInt::one();
+        let mut prev = BigInt::one();
+        for k in 0..n - 1 {
+            let Some(pivot) = (k..n).find(|r| !m[(*r, k)].is_zero()) else {
+                return BigInt::zero();
+            };
+            if pivot != k {
+                m.swap_rows(k, pivot);
+                sign = -sign;
+            }
+            for (i, j) in iproduct!(k + 1..n, k + 1..n) {
+                m[(i, j)] = (&m[(i, j)] * &m[(k, k)] - &m[(i, k)] * &m[(k, j)]) / &prev;
+            }
+            prev = m[(k, k)].clone();
+        }
+        sign * &m[(n - 1, n - 1)]
+    }
+
+    pub fn hermite_normal_form(&self) -> HermiteForm {
+        let mut h = self.clone();
+        let mut u = Matrix::identity(self.rows);
+        let mut pivots = vec![];
+        for col in 0..self.cols {
+            let row = pivots.len();
+            if row == self.rows {
+                break;
+            }
+            // Euclid on the column: reduce everything below by the smallest entry until only
+            // one entry is left
+            while let Some(smallest) = (row..self.rows)
+                .filter(|r| !h[(*r, col)].is_zero())
+                .min_by_key(|r| h[(*r, col)].abs())
+            {
+                h.swap_rows(row, smallest);
+                u.swap_rows(row, smallest);
+                let pivot = h[(row, col)].clone();
+                for r in row + 1..self.rows {
+                    let q = h[(r, col)].div_floor(&pivot);
+                    h.sub_row_multiple(r, row, &q);
+                    u.sub_row_multiple(r, row, &q);
+                }
+                if (row + 1..self.rows).all(|r| h[(r, col)].is_zero()) {
+                    pivots.push(col);
+                    break;
+                }
+            }
+            if pivots.len() == row {
+                continue;
+            }
+            if h[(row, col)].is_negative() {
+                h.scale_row(row, &-BigInt::one());
+                u.scale_row(row, &-BigInt::one());
+            }
+            let pivot = h[(row, col)].clone();
+            for r in 0..row {
+                let q = h[(r, col)].div_floor(&pivot);
+                h.sub_row_multiple(r, row, &q);
+                u.sub_row_multiple(r, row, &q);
+            }
+        }
+        HermiteForm { h, u, pivots }
+    }
+
+    pub fn smith_normal_form(&self) -> SmithForm {
+        let (m, n) = (self.rows, self.cols);
+        let mut d = self.clone();
+        let mut u = Matrix::identity(m);
+        let mut v = Matrix::identity(n);
+        for t in 0..m.min(n) {
+            loop {
+                let Some((pr, pc)) = iproduct!(t..m, t..n)
+                    .filter(|(r, c)| !d[(*r, *c)].is_zero())
+                    .min_by_key(|(r, c)| d[(*r, *c)].abs())
+                else {
+                    return SmithForm { d, u, v };
+                };
+                d.swap_rows(t, pr);
+                u.swap_rows(t, pr);
+                d.swap_cols(t, pc);
+                v.swap_cols(t, pc);
+                let pivot = d[(t, t)].clone();
+                for r in t + 1..m {
+                    let q = d[(r, t)].div_floor(&pivot);
+                    d.sub_row_multiple(r, t, &q);
+                    u.sub_row_multiple(r, t, &q);
+                }
+                for c in t + 1..n {
+                    let q = d[(t, c)].div_floor(&pivot);
+                    d.sub_col_multiple(c, t, &q);
+                    v.sub_col_multiple(c, t, &q);
+                }
+                let cleared = (t + 1..m).all(|r| d[(r, t)].is_zero())
+                    && (t + 1..n).all(|c| d[(t, c)].is_zero());
+                if !cleared {
+                    continue;
+                }
+                // The pivot has to divide the rest; if not, adding the offending row leaves a
+                // smaller remainder for the next round
+                let Some(r) =
+                    (t + 1..m).find(|r| (t + 1..n).any(|c| !(&d[(*r, c)] % &pivot).is_zero()))
+                else {
+                    break;
+                };
+                d.sub_row_multiple(t, r, &-BigInt::one());
+                u.sub_row_multiple(t, r, &-BigInt::one());
+            }
+            if d[(t, t)].is_negative() {
+                d.scale_row(t, &-BigInt::one());
+                u.scale_row(t, &-BigInt::one());
+            }
+        }
+        SmithForm { d, u, v }
+    }
+
+    /// All integer solutions of `self · x = b`, or `None` if there are none.
+    pub fn solve_integer(&self, b: &[BigInt]) -> Option<AffineSolution<BigInt>> {
+        assert_eq!(b.len(), self.rows, "Dimension mismatch");
+        // With y = v⁻¹ · x the system becomes d · y = u · b
+        let SmithForm { d, u, v } = self.smith_normal_form();
+        let c = u.mul_vec(b);
+        let rank = (0..self.rows.min(self.cols))
+            .take_while(|i| !d[(*i, *i)].is_zero())
+            .count();
+        let mut y = vec![BigInt::zero(); self.cols];
+        for (i, ci) in c.iter().enumerate() {
+            if i < rank {
+                let (q, rem) = ci.div_rem(&d[(i, i)]);
+                if !rem.is_zero() {
+                    return None;
+                }
+                y[i] = q;
+            } else if !ci.is_zero() {
+                return None;
+            }
+        }
+        Some(AffineSolution {
+            particular: v.mul_vec(&y),
+            null_space: (rank..self.cols).map(|it| v.column(it)).collect(),
+        })
+    }
+}
+
+impl SmithForm {
+    /// The non-zero diagonal entries of `d`.
+    pub fn invariant_factors(&self) -> Vec<BigInt> {
+        (0..self.d.rows.min(self.d.cols))
+            .map(|i| self.d[(i, i)].clone())
+            .take_while(|it| !it.is_zero())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn int(rows: &[&[i64]]) -> Matrix<BigInt> {
+        Matrix::from_rows(rows.iter().map(|it| it.to_vec()).collect()).map(|it| BigInt::from(*it))
+    }
+
+    fn rational(rows: &[&[i64]]) -> Matrix<Rational> {
+        int(rows).to_rational()
+    }
+
+    fn q(values: &[i64]) -> Vec<Rational> {
+        values
+            .iter()
+            .map(|it| Rational::from_integer(BigInt::from(*it)))
+            .collect()
+    }
+
+    #[test]
+    fn solves_rational_systems() {
+        let a = rational(&[&[2, 1, -1], &[-3, -1, 2], &[-2, 1, 2]]);
+        let solution = a.solve(&q(&[8, -11, -3])).unwrap();
+        assert_eq!(solution.particular, q(&[2, 3, -1]));
+        assert_eq!(solution.dimension(), 0);
+        assert_eq!(a.determinant(), q(&[-1])[0]);
+        assert_eq!(&a.inverse().unwrap() * &a, Matrix::identity(3));
+
+        let b = rational(&[&[1, 2, 3], &[2, 4, 6]]);
+        assert_eq!(b.rank(), 1);
+        let solution = b.solve(&q(&[6, 12])).unwrap();
+        assert_eq!(solution.dimension(), 2);
+        assert_eq!(b.mul_vec(&solution.at(&q(&[5, -7]))), q(&[6, 12]));
+        assert_eq!(b.solve(&q(&[6, 13])), None);
+        assert_eq!(rational(&[&[1, 2], &[2, 4]]).inverse(), None);
+        let empty = Matrix::<Rational>::identity(0);
+        assert_eq!(empty.inverse(), Some(empty));
+    }
+
+    #[test]
+    fn bareiss_matches_rational_determinant() {
+        let matrices = [
+            int(&[&[3, 2, 0, 1], &[4, 0, 1, 2], &[3, 0, 2, 1], &[9, 2, 3, 1]]),
+            int(&[&[0, 1], &[1, 0]]),
+            int(&[&[1, 2, 3], &[4, 5, 6], &[7, 8, 9]]),
+            int(&[&[0, 0, 5], &[0, 7, 1], &[3, 2, 1]]),
+        ];
+        assert_eq!(matrices[0].determinant(), BigInt::from(24));
+        for m in matrices {
+            assert_eq!(
+                Rational::from_integer(m.determinant()),
+                m.to_rational().determinant()
+            );
+        }
+    }
+
+    #[test]
+    fn computes_normal_forms_and_integer_solutions() {
+        let a = int(&[&[2, 4, 4], &[-6, 6, 12], &[10, -4, -16]]);
+
+        let smith = a.smith_normal_form();
+        assert_eq!(&(&smith.u * &a) * &smith.v, smith.d);
+        assert_eq!(smith.invariant_factors(), [2, 6, 12].map(BigInt::from));
+        assert_eq!(smith.u.determinant().abs(), BigInt::one());
+        assert_eq!(smith.v.determinant().abs(), BigInt::one());
+
+        let hermite = a.hermite_normal_form();
+        assert_eq!(&hermite.u * &a, hermite.h);
+        assert_eq!(hermite.pivots, vec![0, 1, 2]);
+        for (r, p) in hermite.pivots.iter().enumerate() {
+            let pivot = &hermite.h[(r, *p)];
+            assert!(pivot.is_positive());
+            assert!((0..r).all(|above| &hermite.h[(above, *p)] < pivot));
+            assert!((0..*p).all(|c| hermite.h[(r, c)].is_zero()));
+        }
+
+        let b = int(&[&[2, 4, 6]]);
+        let solution = b.solve_integer(&[BigInt::from(8)]).unwrap();
+        assert_eq!(solution.dimension(), 2);
+        let x = solution.at(&[BigInt::from(3), BigInt::from(-2)]);
+        assert_eq!(b.mul_vec(&x), [BigInt::from(8)]);
+        assert_eq!(b.solve_integer(&[BigInt::from(7)]), None);
+    }
+}
@@ -0,0 +1,250 @@
+use num::{Integer, PrimInt, Signed};
+
+pub fn gcd_all<T: Integer + Copy>(values: &[T]) -> T {
+    values.iter().fold(T::zero(), |acc, it| acc.gcd(it))
+}
+
+pub fn lcm_all<T: Integer + Copy>(values: &[T]) -> T {
+    values.iter().fold(T::one(), |acc, it| acc.lcm(it))
+}
+
+/// `(g, x, y)` with `g = gcd(a, b) = a·x + b·y` and `g >= 0`.
+pub fn extended_gcd<T: PrimInt + Signed>(a: T, b: T) -> (T, T, T) {
+    let (mut r0, mut r1) = (a, b);
+    let (mut x0, mut x1) = (T::one(), T::zero());
+    let (mut y0, mut y1) = (T::zero(), T::one());
+    while !r1.is_zero() {
+        let q = r0 / r1;
+        (r0, r1) = (r1, r0 - q * r1);
+        (x0, x1) = (x1, x0 - q * x1);
+        (y0, y1) = (y1, y0 - q * y1);
+    }
+    if r0.is_negative() {
+        (-r0, -x0, -y0)
+    } else {
+        (r0, x0, y0)
+    }
+}
+
+/// `x` in `0..m` with `a·x ≡ 1 (mod m)`, if `a` and `m` are coprime.
+pub fn mod_inverse<T: PrimInt + Signed>(a: T, m: T) -> Option<T> {
+    let (g, x, _) = extended_gcd(a, m);
+    g.is_one().then(|| ((x % m) + m) % m)
+}
+
+/// `base^exp mod m` by repeated squaring, in `0..m` even for a negative `base`.
+///
+/// The products are taken in `u128`, so `m` can be anything in `1..=u64::MAX`.
+pub fn mod_pow<T: PrimInt>(base: T, mut exp: T, m: T) -> T {
+    let modulus = m
+        .to_u64()
+        .filter(|it| *it > 0)
+        .expect("Modulus must be in 1..=u64::MAX");
+    assert!(exp >= T::zero(), "Negative exponent");
+    let m128 = u128::from(modulus);
+    // |base % m| < m, so the remainder fits into an i128 whatever T is
+    let mut base = (base % m)
+        .to_i128()
+        .unwrap()
+        .rem_euclid(i128::from(modulus))
+        .unsigned_abs();
+    let mut result = 1 % m128;
+    while !exp.is_zero() {
+        if (exp & T::one()).is_one() {
+            result = result * base % m128;
+        }
+        base = base * base % m128;
+        exp = exp >> 1;
+    }
+    T::from(result).unwrap()
+}
+
+/// Combines congruences `x ≡ residue (mod modulus)` into a single `(residue, lcm of moduli)`.
+///
+/// The moduli need not be coprime and a negative one means the same as its absolute value;
+/// `None` if the congruences contradict each other. Panics for a zero modulus or once the lcm
+/// of the moduli does not fit into an `i64`.
+pub fn crt(congruences: &[(i64, i64)]) -> Option<(i64, i64)> {
+    let mut result = (0i128, 1i128);
+    for (r, m) in congruences {
+        assert_ne!(*m, 0, "Modulus must not be zero");
+        let (r, m) = (i128::from(*r), i128::from(*m).abs());
+        let (r0, m0) = result;
+        let (g, p, _) = extended_gcd(m0, m);
+        if (r - r0) % g != 0 {
+            return None;
+        }
+        // Both moduli fit into an i64, so none of these products overflow an i128
+        let lcm = m0 / g * m;
+        assert!(
+            lcm <= i128::from(i64::MAX),
+            "Combined modulus {} does not fit into an i64",
+            lcm
+        );
+        let k = (r - r0) / g % (m / g) * p % (m / g);
+        result = ((r0 + m0 * k).rem_euclid(lcm), lcm);
+    }
+    Some((
+        i64::try_from(result.0).unwrap(),
+        i64::try_from(result.1).unwrap(),
+    ))
+}
+
+/// Number of decimal digits, with 0 having one.
+pub fn digit_count(n: u64) -> u32 {
+    n.checked_ilog10().map_or(1, |it| it + 1)
+}
+
+/// `10^exp`, for `exp` up to 19.
+pub fn pow10(exp: u32) -> u64 {
+    10u64
+        .checked_pow(exp)
+        .unwrap_or_else(|| panic!("10^{} does not fit into a u64", exp))
+}
+
+/// The number made of `digits` ones, for `digits` up to 19.
+pub fn repunit(digits: u32) -> u64 {
+    (pow10(digits) - 1) / 9
+}
+
+/// `(10^(block·repeats) − 1) / (10^block − 1)`, which turns any `block` digit number into that
+/// number written `repeats` times in a row, e.g. `block_repunit(2, 3) * 12 = 121212`.
+///
+/// Worked out in `u128`, so the written out numbers may have up to 38 digits as long as the
+/// result itself fits into a `u64`.
+pub fn block_repunit(block: u32, repeats: u32) -> u64 {
+    let digits = block
+        .checked_mul(repeats)
+        .filter(|it| *it <= 38)
+        .expect("Repeated blocks do not fit into a u128");
+    let result = (10u128.pow(digits) - 1) / (10u128.pow(block) - 1);
+    u64::try_from(result).expect("Block repunit does not fit into a u64")
+}
+
+/// Sum of `first..=last`, which always fits into a `u128`.
+pub fn series_sum(first: u64, last: u64) -> u128 {
+    if last < first {
+        return 0;
+    }
+    (u128::from(first) + u128::from(last)) * u128::from(last - first + 1) / 2
+}
+
+pub fn count_multiples(m: u64, lo: u64, hi: u64) -> u64 {
+    (hi / m + 1).saturating_sub(lo.div_ceil(m))
+}
+
+/// Sum of all multiples of `m` in `lo..=hi`, which always fits into a `u128`.
+pub fn sum_of_multiples(m: u64, lo: u64, hi: u64) -> u128 {
+    series_sum(lo.div_ceil(m), hi / m)
+        .checked_mul(u128::from(m))
+        .unwrap()
+}
+
+/// All positive divisors of `n`, ascending.
+pub fn divisors<T: PrimInt>(n: T) -> Vec<T> {
+    let mut small = vec![];
+    let mut large = vec![];
+    let mut d = T::one();
+    while d <= n / d {
+        if (n % d).is_zero() {
+            small.push(d);
+            if d * d != n {
+                large.push(n / d);
+            }
+        }
+        d = d + T::one();
+    }
+    small.extend(large.into_iter().rev());
+    small
+}
+
+/// Möbius function: 0 if `n` has a square factor, otherwise -1 or 1 for an odd or even number of
+/// prime factors.
+pub fn mobius<T: PrimInt>(mut n: T) -> i64 {
+    let mut result = 1;
+    let mut p = T::one() + T::one();
+    while p <= n / p {
+        if (n % p).is_zero() {
+            n = n / p;
+            if (n % p).is_zero() {
+                return 0;
+            }
+            result = -result;
+        }
+        p = p + T::one();
+    }
+    if n > T::one() { -result } else { result }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn solves_congruences() {
+        assert_eq!(gcd_all(&[12, 18, 30]), 6);
+        assert_eq!(lcm_all(&[4, 6, 10]), 60);
+        let (g, x, y) = extended_gcd(240i64, 46);
+        assert_eq!((g, 240 * x + 46 * y), (2, 2));
+        assert_eq!(mod_inverse(3i64, 11), Some(4));
+        assert_eq!(mod_inverse(4i64, 10), None);
+        assert_eq!(
+            mod_pow(2u64, 62, 1_000_000_007),
+            (1u64 << 62) % 1_000_000_007
+        );
+        assert_eq!(mod_pow(-3i32, 3, 7), 1);
+        assert_eq!(mod_pow(u64::MAX - 1, 2, u64::MAX), 1);
+        assert_eq!(crt(&[(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        assert_eq!(crt(&[(3, 4), (5, 6)]), Some((11, 12)));
+        assert_eq!(crt(&[(1, 4), (2, 6)]), None);
+        assert_eq!(crt(&[(2, -3), (-2, 5)]), Some((8, 15)));
+        assert_eq!(crt(&[(5, i64::MAX)]), Some((5, i64::MAX)));
+    }
+
+    #[test]
+    #[should_panic(expected = "does not fit into an i64")]
+    fn rejects_moduli_beyond_i64() {
+        crt(&[(0, 1 << 62), (1, 3)]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Modulus must not be zero")]
+    fn rejects_a_zero_modulus() {
+        crt(&[(1, 3), (0, 0)]);
+    }
+
+    #[test]
+    fn builds_repeated_digits() {
+        assert_eq!(digit_count(0), 1);
+        assert_eq!(digit_count(9), 1);
+        assert_eq!(digit_count(1_000_000), 7);
+        assert_eq!(repunit(4), 1111);
+        assert_eq!(block_repunit(2, 3) * 12, 121212);
+        assert_eq!(block_repunit(3, 2) * 456, 456456);
+        assert_eq!(block_repunit(10, 2), 10_000_000_001);
+    }
+
+    #[test]
+    fn counts_and_sums_multiples() {
+        for (m, lo, hi) in [(7, 0, 100), (3, 10, 11), (5, 20, 20), (11, 95, 1234)] {
+            let brute = (lo..=hi).filter(|it| it % m == 0);
+            assert_eq!(count_multiples(m, lo, hi), brute.clone().count() as u64);
+            assert_eq!(sum_of_multiples(m, lo, hi), u128::from(brute.sum::<u64>()));
+        }
+        assert_eq!(series_sum(5, 4), 0);
+        assert_eq!(
+            sum_of_multiples(1, u64::MAX - 1, u64::MAX),
+            2 * u128::from(u64::MAX) - 1
+        );
+        assert_eq!(divisors(36u32), vec![1, 2, 3, 4, 6, 9, 12, 18, 36]);
+        // u32::MAX = 3 · 5 · 17 · 257 · 65537 and i16::MAX = 7 · 31 · 151, reached without overflow
+        assert_eq!(divisors(u32::MAX).len(), 32);
+        assert_eq!(divisors(u8::MAX), vec![1, 3, 5, 15, 17, 51, 85, 255]);
+        assert_eq!(mobius(u32::MAX), -1);
+        assert_eq!(mobius(i16::MAX), -1);
+        assert_eq!(
+            (1..=10u32).map(mobius).collect::<Vec<_>>(),
+            vec![1, -1, -1, 0, -1, 1, -1, 0, 0, 1]
+        );
+    }
+}
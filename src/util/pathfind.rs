@@ -0,0 +1,221 @@
+use crate::util::{Dir, Point};
+use itertools::Itertools;
+use pathfinding::prelude::{
+    astar, astar_bag_collect, bfs as bfs_nodes, dijkstra as dijkstra_nodes,
+};
+
+/// Shortest path including start and end, together with its total cost.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GridPath {
+    pub points: Vec<Point>,
+    pub cost: usize,
+}
+
+/// Position plus the direction it is facing, for puzzles where turning has a cost.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Heading {
+    pub pos: Point,
+    pub dir: Dir,
+}
+
+impl Heading {
+    pub fn new(pos: Point, dir: Dir) -> Heading {
+        Heading { pos, dir }
+    }
+
+    /// Stepping forward, or turning left or right on the spot.
+    pub fn moves(&self) -> [Heading; 3] {
+        [
+            Heading::new(self.pos.mv(self.dir), self.dir),
+            Heading::new(self.pos, self.dir.cw()),
+            Heading::new(self.pos, self.dir.ccw()),
+        ]
+    }
+}
+
+/// Cost function for `Heading` searches: `step` per move onto a passable cell, `turn` per 90°.
+pub fn turning_cost(
+    step: usize,
+    turn: usize,
+    passable: impl Fn(&Point) -> bool,
+) -> impl Fn(&Heading, &Heading) -> Option<usize> {
+    move |from, to| {
+        if from.pos == to.pos {
+            Some(turn)
+        } else if passable(&to.pos) {
+            Some(step)
+        } else {
+            None
+        }
+    }
+}
+
+fn successors(p: &Point, cost: &impl Fn(&Point, &Point) -> Option<usize>) -> Vec<(Point, usize)> {
+    p.neighbours()
+        .into_iter()
+        .filter_map(|n| cost(p, &n).map(|c| (n, c)))
+        .collect_vec()
+}
+
+fn heading_successors(
+    h: &Heading,
+    cost: &impl Fn(&Heading, &Heading) -> Option<usize>,
+) -> Vec<(Heading, usize)> {
+    h.moves()
+        .into_iter()
+        .filter_map(|n| cost(h, &n).map(|c| (n, c)))
+        .collect_vec()
+}
+
+fn heading_path(states: Vec<Heading>, cost: usize) -> GridPath {
+    GridPath {
+        points: states.into_iter().map(|it| it.pos).dedup().collect(),
+        cost,
+    }
+}
+
+/// Fewest steps between 4-neighbours for which `passable` holds.
+pub fn bfs(
+    start: &Point,
+    passable: impl Fn(&Point) -> bool,
+    goal: impl Fn(&Point) -> bool,
+) -> Option<GridPath> {
+    bfs_nodes(
+        start,
+        |p| {
+            p.neighbours()
+                .into_iter()
+                .filter(|n| passable(n))
+                .collect_vec()
+        },
+        goal,
+    )
+    .map(|points| GridPath {
+        cost: points.len() - 1,
+        points,
+    })
+}
+
+/// Cheapest path where `cost(from, to)` prices each step to a 4-neighbour, `None` meaning blocked.
+pub fn dijkstra(
+    start: &Point,
+    cost: impl Fn(&Point, &Point) -> Option<usize>,
+    goal: impl Fn(&Point) -> bool,
+) -> Option<GridPath> {
+    dijkstra_nodes(start, |p| successors(p, &cost), goal)
+        .map(|(points, cost)| GridPath { points, cost })
+}
+
+/// Like `dijkstra` towards a single goal, guided by the Manhattan distance.
+///
+/// Only finds the cheapest path if every step costs at least 1.
+pub fn astar_manhattan(
+    start: &Point,
+    goal: &Point,
+    cost: impl Fn(&Point, &Point) -> Option<usize>,
+) -> Option<GridPath> {
+    astar(
+        start,
+        |p| successors(p, &cost),
+        |p| p.manhattan(goal) as usize,
+        |p| p == goal,
+    )
+    .map(|(points, cost)| GridPath { points, cost })
+}
+
+/// Every cheapest path to `goal`. Their number can grow exponentially in open areas.
+pub fn all_shortest_paths(
+    start: &Point,
+    goal: &Point,
+    cost: impl Fn(&Point, &Point) -> Option<usize>,
+) -> Vec<GridPath> {
+    astar_bag_collect(
+        start,
+        |p| successors(p, &cost),
+        |p| p.manhattan(goal) as usize,
+        |p| p == goal,
+    )
+    .map(|(paths, cost)| {
+        paths
+            .into_iter()
+            .map(|points| GridPath { points, cost })
+            .collect()
+    })
+    .unwrap_or_default()
+}
+
+/// Cheapest path over `Heading`s, e.g. with `turning_cost`.
+pub fn dijkstra_heading(
+    start: &Heading,
+    cost: impl Fn(&Heading, &Heading) -> Option<usize>,
+    goal: impl Fn(&Heading) -> bool,
+) -> Option<GridPath> {
+    dijkstra_nodes(start, |h| heading_successors(h, &cost), goal)
+        .map(|(states, cost)| heading_path(states, cost))
+}
+
+/// Every cheapest path over `Heading`s, one per distinct sequence of states.
+pub fn all_shortest_paths_heading(
+    start: &Heading,
+    cost: impl Fn(&Heading, &Heading) -> Option<usize>,
+    goal: impl Fn(&Heading) -> bool,
+) -> Vec<GridPath> {
+    astar_bag_collect(start, |h| heading_successors(h, &cost), |_| 0, goal)
+        .map(|(paths, cost)| {
+            paths
+                .into_iter()
+                .map(|states| heading_path(states, cost))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::Dir::{Right, Up};
+    use crate::util::GridAccess;
+    use array2d::Array2D;
+
+    fn maze() -> Array2D<char> {
+        let rows = [
+            "#######", "#S....#", "#.###.#", "#.....#", "#.#####", "#....E#", "#######",
+        ];
+        Array2D::from_rows(&rows.iter().map(|it| it.chars().collect()).collect_vec()).unwrap()
+    }
+
+    #[test]
+    fn finds_shortest_paths_in_a_maze() {
+        let maze = maze();
+        let open = |p: &Point| maze.get_point(p).is_some_and(|c| *c != '#');
+        let step = |_: &Point, to: &Point| open(to).then_some(1);
+        let (start, end) = (Point::new(1, 1), Point::new(5, 5));
+
+        let path = bfs(&start, open, |p| *p == end).unwrap();
+        assert_eq!(path.cost, 8);
+        assert_eq!(path.points.len(), 9);
+        assert_eq!(dijkstra(&start, step, |p| *p == end).unwrap().cost, 8);
+        assert_eq!(astar_manhattan(&start, &end, step).unwrap().cost, 8);
+        assert_eq!(all_shortest_paths(&start, &Point::new(5, 3), step).len(), 2);
+        assert_eq!(bfs(&start, open, |p| *p == Point::new(0, 0)), None);
+    }
+
+    #[test]
+    fn pays_for_turns() {
+        let maze = maze();
+        let cost = turning_cost(1, 1000, |p| maze.get_point(p).is_some_and(|c| *c != '#'));
+        let start = Heading::new(Point::new(1, 1), Right);
+        let goal = |h: &Heading| h.pos == Point::new(5, 3);
+
+        let path = dijkstra_heading(&start, &cost, goal).unwrap();
+        assert_eq!(path.cost, 6 + 1000);
+        assert_eq!(path.points.last(), Some(&Point::new(5, 3)));
+        assert_eq!(all_shortest_paths_heading(&start, &cost, goal).len(), 1);
+
+        let facing_up = Heading::new(Point::new(1, 1), Up);
+        assert_eq!(
+            dijkstra_heading(&facing_up, &cost, goal).unwrap().cost,
+            6 + 2000
+        );
+    }
+}
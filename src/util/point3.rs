@@ -0,0 +1,213 @@
+use crate::util::Coord;
+use itertools::iproduct;
+use num::iter::range_inclusive;
+use prse::{Parse, try_parse};
+use std::borrow::Borrow;
+use std::fmt::{Display, Formatter};
+use std::ops::{Add, Mul, Neg, RangeInclusive, Sub};
+use std::str::FromStr;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, Ord, PartialOrd, Default)]
+pub struct Point3<T = i64> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+}
+
+impl<T: Display> Display for Point3<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "<{}, {}, {}>", self.x, self.y, self.z)
+    }
+}
+
+impl<T: for<'a> Parse<'a>> FromStr for Point3<T> {
+    type Err = prse::ParseError;
+
+    /// Parses `x,y,z`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (x, y, z) = try_parse!(s, "{},{},{}")?;
+        Ok(Point3 { x, y, z })
+    }
+}
+
+impl<T: Coord> Add for Point3<T> {
+    type Output = Point3<T>;
+
+    fn add(self, rhs: Point3<T>) -> Point3<T> {
+        Point3::new(self.x + rhs.x, self.y + rhs.y, self.z + rhs.z)
+    }
+}
+
+impl<T: Coord> Sub for Point3<T> {
+    type Output = Point3<T>;
+
+    fn sub(self, rhs: Point3<T>) -> Point3<T> {
+        Point3::new(self.x - rhs.x, self.y - rhs.y, self.z - rhs.z)
+    }
+}
+
+impl<T: Coord> Mul<T> for Point3<T> {
+    type Output = Point3<T>;
+
+    fn mul(self, rhs: T) -> Point3<T> {
+        Point3::new(self.x * rhs, self.y * rhs, self.z * rhs)
+    }
+}
+
+impl<T: Coord> Neg for Point3<T> {
+    type Output = Point3<T>;
+
+    fn neg(self) -> Point3<T> {
+        Point3::new(-self.x, -self.y, -self.z)
+    }
+}
+
+impl<T: Coord> Point3<T> {
+    pub fn new(x: T, y: T, z: T) -> Point3<T> {
+        Point3 { x, y, z }
+    }
+
+    /// Point with all three coordinates `n`.
+    pub fn splat(n: T) -> Point3<T> {
+        Point3::new(n, n, n)
+    }
+
+    pub fn manhattan(&self, other: &Point3<T>) -> T {
+        let d = *self - *other;
+        d.x.abs() + d.y.abs() + d.z.abs()
+    }
+
+    pub fn chebyshev(&self, other: &Point3<T>) -> T {
+        let d = *self - *other;
+        d.x.abs().max(d.y.abs()).max(d.z.abs())
+    }
+
+    /// Squared Euclidean distance, which orders points like the real distance without rounding.
+    pub fn dist_sq(&self, other: &Point3<T>) -> T {
+        let d = *self - *other;
+        d.x * d.x + d.y * d.y + d.z * d.z
+    }
+
+    /// The 6 points sharing a face.
+    pub fn neighbours(&self) -> Vec<Point3<T>> {
+        let (zero, one) = (T::zero(), T::one());
+        [
+            Point3::new(one, zero, zero),
+            Point3::new(-one, zero, zero),
+            Point3::new(zero, one, zero),
+            Point3::new(zero, -one, zero),
+            Point3::new(zero, zero, one),
+            Point3::new(zero, zero, -one),
+        ]
+        .into_iter()
+        .map(|it| *self + it)
+        .collect()
+    }
+
+    /// The 26 points sharing a face, an edge or a corner.
+    pub fn neighbours_diagonal(&self) -> Vec<Point3<T>> {
+        let offsets = || range_inclusive(-T::one(), T::one());
+        iproduct!(offsets(), offsets(), offsets())
+            .map(|(x, y, z)| Point3::new(x, y, z))
+            .filter(|it| *it != Point3::splat(T::zero()))
+            .map(|it| *self + it)
+            .collect()
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct Bounds3<T = i64> {
+    pub min: Point3<T>,
+    pub max: Point3<T>,
+}
+
+impl<T: Coord> Bounds3<T> {
+    /// Smallest bounds containing all points, or `None` if there are none.
+    pub fn from_points<I>(points: I) -> Option<Bounds3<T>>
+    where
+        I: IntoIterator,
+        I::Item: Borrow<Point3<T>>,
+    {
+        points.into_iter().fold(None, |acc, p| {
+            let p = *p.borrow();
+            Some(match acc {
+                None => Bounds3 { min: p, max: p },
+                Some(b) => Bounds3 {
+                    min: Point3::new(b.min.x.min(p.x), b.min.y.min(p.y), b.min.z.min(p.z)),
+                    max: Point3::new(b.max.x.max(p.x), b.max.y.max(p.y), b.max.z.max(p.z)),
+                },
+            })
+        })
+    }
+
+    pub fn xs(&self) -> RangeInclusive<T> {
+        self.min.x..=self.max.x
+    }
+
+    pub fn ys(&self) -> RangeInclusive<T> {
+        self.min.y..=self.max.y
+    }
+
+    pub fn zs(&self) -> RangeInclusive<T> {
+        self.min.z..=self.max.z
+    }
+
+    pub fn volume(&self) -> T {
+        let d = self.max - self.min + Point3::splat(T::one());
+        d.x * d.y * d.z
+    }
+
+    pub fn contains(&self, p: &Point3<T>) -> bool {
+        self.xs().contains(&p.x) && self.ys().contains(&p.y) && self.zs().contains(&p.z)
+    }
+
+    pub fn expand(&self, n: T) -> Bounds3<T> {
+        Bounds3 {
+            min: self.min - Point3::splat(n),
+            max: self.max + Point3::splat(n),
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Point3<T>> + use<T> {
+        iproduct!(
+            range_inclusive(self.min.x, self.max.x),
+            range_inclusive(self.min.y, self.max.y),
+            range_inclusive(self.min.z, self.max.z)
+        )
+        .map(|(x, y, z)| Point3::new(x, y, z))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_and_measures() {
+        let a: Point3 = "162,817,812".parse().unwrap();
+        let b: Point3 = "57,618,57".parse().unwrap();
+
+        assert_eq!(a, Point3::new(162, 817, 812));
+        assert_eq!(a.dist_sq(&b), 105 * 105 + 199 * 199 + 755 * 755);
+        assert_eq!(a.manhattan(&b), 105 + 199 + 755);
+        assert_eq!(a.chebyshev(&b), 755);
+        assert_eq!(b + (a - b), a);
+        assert_eq!((a - b) * 2, a - b + (a - b));
+        assert!("1,2".parse::<Point3>().is_err());
+        let small: Point3<i8> = "1,-2,3".parse().unwrap();
+        assert_eq!(small.manhattan(&Point3::splat(0)), 6);
+    }
+
+    #[test]
+    fn iterates_neighbourhoods_and_bounds() {
+        let p = Point3::new(1, 2, 3);
+        let cube = Bounds3::from_points(p.neighbours_diagonal()).unwrap();
+
+        assert_eq!(p.neighbours().len(), 6);
+        assert_eq!(p.neighbours_diagonal().len(), 26);
+        assert_eq!(cube, Bounds3 { min: p, max: p }.expand(1));
+        assert_eq!(cube.volume(), 27);
+        assert_eq!(cube.iter().filter(|it| *it != p).count(), 26);
+        assert!(cube.iter().all(|it| cube.contains(&it)));
+    }
+}
@@ -0,0 +1,165 @@
+use crate::util::Point64;
+use itertools::Itertools;
+
+/// Closed rectilinear polygon through lattice points, e.g. a loop of tiles.
+///
+/// Consecutive vertices (and the last and the first) must share an `x` or a `y` coordinate. The
+/// polygon is taken to be simple and closed, so points on its boundary count as inside.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Polygon {
+    vertices: Vec<Point64>,
+}
+
+impl Polygon {
+    pub fn new(vertices: Vec<Point64>) -> Polygon {
+        let polygon = Polygon { vertices };
+        assert!(
+            polygon.edges().all(|(a, b)| a.x == b.x || a.y == b.y),
+            "Polygon is not rectilinear"
+        );
+        polygon
+    }
+
+    pub fn vertices(&self) -> &[Point64] {
+        &self.vertices
+    }
+
+    pub fn edges(&self) -> impl Iterator<Item = (Point64, Point64)> + '_ {
+        self.vertices.iter().copied().circular_tuple_windows()
+    }
+
+    /// Enclosed area by the shoelace formula, taking the vertices as points without extent.
+    pub fn area(&self) -> i64 {
+        self.edges()
+            .map(|(a, b)| a.x * b.y - b.x * a.y)
+            .sum::<i64>()
+            .abs()
+            / 2
+    }
+
+    /// Number of lattice points on the boundary, which is also its length.
+    pub fn boundary_len(&self) -> i64 {
+        self.edges().map(|(a, b)| a.manhattan(&b)).sum()
+    }
+
+    /// Lattice points strictly inside, by Pick's theorem.
+    pub fn interior_points(&self) -> i64 {
+        self.area() - self.boundary_len() / 2 + 1
+    }
+
+    /// Lattice points inside or on the boundary, i.e. the number of tiles the loop encloses.
+    pub fn tile_count(&self) -> i64 {
+        self.interior_points() + self.boundary_len()
+    }
+
+    pub fn contains(&self, p: &Point64) -> bool {
+        self.contains_doubled(&(*p * 2))
+    }
+
+    /// Like `contains` for a polygon scaled by 2, so half coordinates can be tested as well.
+    fn contains_doubled(&self, q: &Point64) -> bool {
+        let mut inside = false;
+        for (a, b) in self.edges() {
+            let (a, b) = (a * 2, b * 2);
+            let (x0, x1) = (a.x.min(b.x), a.x.max(b.x));
+            let (y0, y1) = (a.y.min(b.y), a.y.max(b.y));
+            if (x0..=x1).contains(&q.x) && (y0..=y1).contains(&q.y) {
+                return true;
+            }
+            // Cast a ray towards +x, counting each vertex only for the edge above it
+            if a.x == b.x && a.x > q.x && (y0..y1).contains(&q.y) {
+                inside = !inside;
+            }
+        }
+        inside
+    }
+
+    /// Whether every point of the rectangle spanned by the opposite corners `a` and `b` is inside.
+    pub fn contains_rect(&self, a: &Point64, b: &Point64) -> bool {
+        let (x0, x1) = (a.x.min(b.x), a.x.max(b.x));
+        let (y0, y1) = (a.y.min(b.y), a.y.max(b.y));
+        if x0 == x1 || y0 == y1 {
+            return self.contains_segment(&Point64::new(x0, y0), &Point64::new(x1, y1));
+        }
+        // With no edge running through the open rectangle, it is either fully inside or
+        // fully outside, and the centre tells which.
+        let crosses = self.edges().any(|(p, q)| {
+            let (ex0, ex1) = (p.x.min(q.x), p.x.max(q.x));
+            let (ey0, ey1) = (p.y.min(q.y), p.y.max(q.y));
+            ex0 < x1 && ex1 > x0 && ey0 < y1 && ey1 > y0
+        });
+        !crosses && self.contains_doubled(&Point64::new(x0 + x1, y0 + y1))
+    }
+
+    /// Checks an axis-parallel segment at every vertex coordinate along it and halfway between.
+    fn contains_segment(&self, a: &Point64, b: &Point64) -> bool {
+        let horizontal = a.y == b.y;
+        let along = |p: &Point64| if horizontal { p.x } else { p.y };
+        let at = |v: i64| {
+            if horizontal {
+                Point64::new(v, 2 * a.y)
+            } else {
+                Point64::new(2 * a.x, v)
+            }
+        };
+        let (from, to) = (along(a), along(b));
+        let stops = self
+            .vertices
+            .iter()
+            .map(along)
+            .filter(|it| (from..=to).contains(it))
+            .chain([from, to])
+            .sorted()
+            .dedup()
+            .collect_vec();
+        stops.iter().all(|it| self.contains_doubled(&at(2 * it)))
+            && stops
+                .iter()
+                .tuple_windows()
+                .all(|(s, t)| self.contains_doubled(&at(s + t)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example() -> Polygon {
+        let points = ["7,1", "11,1", "11,7", "9,7", "9,5", "2,5", "2,3", "7,3"];
+        Polygon::new(points.iter().map(|it| it.parse().unwrap()).collect())
+    }
+
+    #[test]
+    fn measures_the_loop() {
+        let polygon = example();
+
+        assert_eq!(polygon.area(), 4 * 2 + 9 * 2 + 2 * 2);
+        assert_eq!(polygon.boundary_len(), 30);
+        assert_eq!(polygon.interior_points(), 16);
+        assert_eq!(polygon.tile_count(), 46);
+    }
+
+    #[test]
+    fn tests_points_and_rectangles() {
+        let polygon = example();
+        let p = |x, y| Point64::new(x, y);
+
+        assert!(polygon.contains(&p(8, 4)));
+        assert!(polygon.contains(&p(11, 7)));
+        assert!(polygon.contains(&p(2, 4)));
+        assert!(!polygon.contains(&p(8, 6)));
+        assert!(!polygon.contains(&p(1, 4)));
+        assert!(!polygon.contains(&p(12, 1)));
+
+        assert!(polygon.contains_rect(&p(9, 5), &p(2, 3)));
+        assert!(polygon.contains_rect(&p(9, 7), &p(11, 1)));
+        assert!(polygon.contains_rect(&p(7, 3), &p(11, 1)));
+        assert!(!polygon.contains_rect(&p(2, 5), &p(11, 1)));
+        assert!(!polygon.contains_rect(&p(2, 3), &p(9, 7)));
+        assert!(polygon.contains_rect(&p(2, 5), &p(9, 5)));
+        assert!(polygon.contains_rect(&p(2, 5), &p(11, 5)));
+        assert!(!polygon.contains_rect(&p(2, 5), &p(12, 5)));
+        assert!(!polygon.contains_rect(&p(8, 3), &p(8, 7)));
+        assert!(!polygon.contains_rect(&p(7, 7), &p(11, 7)));
+    }
+}
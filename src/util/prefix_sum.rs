@@ -0,0 +1,185 @@
+use crate::util::transform::Grid;
+use crate::util::{BitSetGrid, Bounds, Point};
+use array2d::Array2D;
+use num::{PrimInt, Zero};
+use std::ops::{Bound, RangeBounds, Sub};
+
+/// Running sums of a sequence for constant time range sums.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrefixSum<T> {
+    /// `sums[i]` is the sum of the first `i` values.
+    sums: Vec<T>,
+}
+
+impl<T: Copy + Zero + Sub<Output = T>> PrefixSum<T> {
+    pub fn new(values: impl IntoIterator<Item = T>) -> PrefixSum<T> {
+        let mut sums = vec![T::zero()];
+        for v in values {
+            sums.push(*sums.last().unwrap() + v);
+        }
+        PrefixSum { sums }
+    }
+
+    pub fn len(&self) -> usize {
+        self.sums.len() - 1
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Sum of the values with an index in `range`, which must lie within `0..len()`.
+    pub fn sum(&self, range: impl RangeBounds<usize>) -> T {
+        let from = match range.start_bound() {
+            Bound::Included(i) => *i,
+            Bound::Excluded(i) => i + 1,
+            Bound::Unbounded => 0,
+        };
+        let to = match range.end_bound() {
+            Bound::Included(i) => i + 1,
+            Bound::Excluded(i) => *i,
+            Bound::Unbounded => self.len(),
+        };
+        if to <= from {
+            return T::zero();
+        }
+        self.sums[to] - self.sums[from]
+    }
+}
+
+/// Summed-area table for constant time sums over rectangles.
+///
+/// Cells outside of the bounds it was built from count as zero.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrefixSum2D<T> {
+    bounds: Bounds,
+    /// Row-major `(height + 1) × (width + 1)` table; entry `(y, x)` sums the `y × x` cells
+    /// above and left of it.
+    sums: Vec<T>,
+}
+
+impl<T: Copy + Zero + Sub<Output = T>> PrefixSum2D<T> {
+    pub fn from_fn(bounds: Bounds, mut value: impl FnMut(Point) -> T) -> PrefixSum2D<T> {
+        let (w, h) = (
+            bounds.width().max(0) as usize,
+            bounds.height().max(0) as usize,
+        );
+        let mut sums = vec![T::zero(); (w + 1) * (h + 1)];
+        for (y, py) in bounds.ys().enumerate() {
+            let mut row = T::zero();
+            for (x, px) in bounds.xs().enumerate() {
+                row = row + value(Point::new(px, py));
+                sums[(y + 1) * (w + 1) + x + 1] = sums[y * (w + 1) + x + 1] + row;
+            }
+        }
+        PrefixSum2D { bounds, sums }
+    }
+
+    /// Sums `value` of every cell of an `Array2D` or `BitSetGrid`.
+    pub fn from_grid<G: Grid>(grid: &G, value: impl Fn(G::Cell) -> T) -> PrefixSum2D<T> {
+        PrefixSum2D::from_fn(grid.grid_bounds(), |p| value(grid.cell(&p)))
+    }
+
+    pub fn from_array2d(grid: &Array2D<T>) -> PrefixSum2D<T> {
+        PrefixSum2D::from_grid(grid, |it| it)
+    }
+
+    pub fn bounds(&self) -> Bounds {
+        self.bounds
+    }
+
+    fn at(&self, x: i32, y: i32) -> T {
+        let w = self.bounds.width() as usize;
+        let x = (x - self.bounds.min_x) as usize;
+        let y = (y - self.bounds.min_y) as usize;
+        self.sums[y * (w + 1) + x]
+    }
+
+    /// Sum over all cells in `b`.
+    pub fn sum(&self, b: &Bounds) -> T {
+        let Some(b) = self.bounds.intersect(b) else {
+            return T::zero();
+        };
+        self.at(b.max_x + 1, b.max_y + 1)
+            - self.at(b.min_x, b.max_y + 1)
+            - self.at(b.max_x + 1, b.min_y)
+            + self.at(b.min_x, b.min_y)
+    }
+}
+
+impl<T: PrimInt> PrefixSum2D<T> {
+    /// Whether every cell of `b` is 1, for tables built from 0/1 values.
+    pub fn is_filled(&self, b: &Bounds) -> bool {
+        self.bounds.contains_bounds(b) && T::from(b.area()) == Some(self.sum(b))
+    }
+}
+
+impl PrefixSum2D<i64> {
+    /// Counts set cells.
+    pub fn from_bit_set_grid(grid: &BitSetGrid) -> PrefixSum2D<i64> {
+        PrefixSum2D::from_grid(grid, i64::from)
+    }
+}
+
+impl<T: Copy + Zero + Sub<Output = T>> From<&Array2D<T>> for PrefixSum2D<T> {
+    fn from(grid: &Array2D<T>) -> Self {
+        PrefixSum2D::from_array2d(grid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use itertools::iproduct;
+
+    #[test]
+    fn sums_ranges() {
+        let sums = PrefixSum::new([3, 1, 4, 1, 5, 9, 2, 6]);
+
+        assert_eq!(sums.len(), 8);
+        assert_eq!(sums.sum(..), 31);
+        assert_eq!(sums.sum(2..5), 10);
+        assert_eq!(sums.sum(2..=5), 19);
+        assert_eq!(sums.sum(7..), 6);
+        assert_eq!(sums.sum(4..4), 0);
+    }
+
+    #[test]
+    fn sums_rectangles_like_brute_force() {
+        let grid =
+            Array2D::from_row_major(&(0..20i64).map(|it| it * it % 7).collect::<Vec<_>>(), 4, 5)
+                .unwrap();
+        let sums = PrefixSum2D::from(&grid);
+
+        for (x0, x1, y0, y1) in iproduct!(-1..6, -1..6, -1..5, -1..5) {
+            let b = Bounds {
+                min_x: x0,
+                max_x: x1,
+                min_y: y0,
+                max_y: y1,
+            };
+            let brute = iproduct!(x0..=x1, y0..=y1)
+                .filter(|(x, y)| (0..5).contains(x) && (0..4).contains(y))
+                .map(|(x, y)| grid[(y as usize, x as usize)])
+                .sum::<i64>();
+            assert_eq!(sums.sum(&b), brute, "{}", b);
+        }
+    }
+
+    #[test]
+    fn finds_filled_rectangles_in_bit_set_grids() {
+        let bits: BitSetGrid = iproduct!(10..15, 20..23)
+            .map(|(x, y)| Point::new(x, y))
+            .filter(|it| *it != Point::new(14, 22))
+            .collect();
+        let sums = PrefixSum2D::from_bit_set_grid(&bits);
+        let rect =
+            |x0, y0, x1, y1| Bounds::from_points([Point::new(x0, y0), Point::new(x1, y1)]).unwrap();
+
+        assert_eq!(sums.sum(&bits.bounds().unwrap()), 14);
+        assert!(sums.is_filled(&rect(10, 20, 14, 21)));
+        assert!(sums.is_filled(&rect(10, 20, 13, 22)));
+        assert!(!sums.is_filled(&rect(10, 20, 14, 22)));
+        assert!(!sums.is_filled(&rect(9, 20, 12, 21)));
+    }
+}
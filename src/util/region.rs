@@ -0,0 +1,233 @@
+use crate::util::Dir::{Down, Left, Right, Up};
+use crate::util::automaton::Neighbourhood;
+use crate::util::{BitSetGrid, Bounds, GridAccess, Point};
+use array2d::Array2D;
+use rustc_hash::FxHashSet;
+use std::collections::HashSet;
+use std::hash::BuildHasher;
+
+/// Connected set of cells.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Region {
+    cells: FxHashSet<Point>,
+}
+
+impl Region {
+    pub fn cells(&self) -> &FxHashSet<Point> {
+        &self.cells
+    }
+
+    pub fn contains(&self, p: &Point) -> bool {
+        self.cells.contains(p)
+    }
+
+    pub fn area(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn bounds(&self) -> Option<Bounds> {
+        Bounds::from_points(&self.cells)
+    }
+
+    /// Number of cell edges bordering cells outside of the region.
+    pub fn perimeter(&self) -> usize {
+        self.cells
+            .iter()
+            .map(|p| p.neighbours().iter().filter(|n| !self.contains(n)).count())
+            .sum()
+    }
+
+    /// Number of straight fence segments around the region, holes included.
+    pub fn sides(&self) -> usize {
+        let is_edge = |p: &Point, d| self.contains(p) && !self.contains(&p.mv(d));
+        [Up, Right, Down, Left]
+            .into_iter()
+            .map(|d| {
+                // Count each side once, at its end furthest in direction `d.ccw()`
+                self.cells
+                    .iter()
+                    .filter(|p| is_edge(p, d) && !is_edge(&p.mv(d.ccw()), d))
+                    .count()
+            })
+            .sum()
+    }
+}
+
+/// All cells within `bounds` reachable from `start` through cells for which `passable` holds.
+///
+/// `start` itself is included even if it is not passable. The bounds keep the fill finite however
+/// much `passable` accepts.
+pub fn flood_fill(
+    start: &Point,
+    bounds: &Bounds,
+    neighbourhood: &Neighbourhood,
+    mut passable: impl FnMut(&Point) -> bool,
+) -> Region {
+    let offsets = neighbourhood.offsets();
+    let mut cells = FxHashSet::default();
+    cells.insert(*start);
+    let mut stack = vec![*start];
+    while let Some(p) = stack.pop() {
+        for (dx, dy) in &offsets {
+            let n = Point::new(p.x + dx, p.y + dy);
+            if bounds.contains(&n) && !cells.contains(&n) && passable(&n) {
+                cells.insert(n);
+                stack.push(n);
+            }
+        }
+    }
+    Region { cells }
+}
+
+/// Connected components of a grid or point set.
+pub trait Components {
+    /// The component containing `p`, if `p` is part of any.
+    fn region_at(&self, p: &Point, neighbourhood: &Neighbourhood) -> Option<Region>;
+    fn components(&self, neighbourhood: &Neighbourhood) -> Vec<Region>;
+}
+
+fn components_of(
+    cells: impl IntoIterator<Item = Point>,
+    bounds: &Bounds,
+    neighbourhood: &Neighbourhood,
+    connected: impl Fn(&Point, &Point) -> bool,
+) -> Vec<Region> {
+    let mut seen: FxHashSet<Point> = FxHashSet::default();
+    let mut regions = vec![];
+    for p in cells {
+        if seen.contains(&p) {
+            continue;
+        }
+        let region = flood_fill(&p, bounds, neighbourhood, |n| connected(&p, n));
+        seen.extend(region.cells.iter().copied());
+        regions.push(region);
+    }
+    regions
+}
+
+impl Components for BitSetGrid {
+    fn region_at(&self, p: &Point, neighbourhood: &Neighbourhood) -> Option<Region> {
+        let bounds = self.bounds()?;
+        self.contains(p)
+            .then(|| flood_fill(p, &bounds, neighbourhood, |n| self.contains(n)))
+    }
+
+    fn components(&self, neighbourhood: &Neighbourhood) -> Vec<Region> {
+        let Some(bounds) = self.bounds() else {
+            return vec![];
+        };
+        components_of(self, &bounds, neighbourhood, |_, n| self.contains(n))
+    }
+}
+
+impl<H: BuildHasher> Components for HashSet<Point, H> {
+    fn region_at(&self, p: &Point, neighbourhood: &Neighbourhood) -> Option<Region> {
+        let bounds = Bounds::from_points(self)?;
+        self.contains(p)
+            .then(|| flood_fill(p, &bounds, neighbourhood, |n| self.contains(n)))
+    }
+
+    fn components(&self, neighbourhood: &Neighbourhood) -> Vec<Region> {
+        let Some(bounds) = Bounds::from_points(self) else {
+            return vec![];
+        };
+        components_of(self.iter().copied(), &bounds, neighbourhood, |_, n| {
+            self.contains(n)
+        })
+    }
+}
+
+/// Components of equal values, with `x` as the column.
+impl<T: PartialEq> Components for Array2D<T> {
+    fn region_at(&self, p: &Point, neighbourhood: &Neighbourhood) -> Option<Region> {
+        let value = self.get_point(p)?;
+        Some(flood_fill(p, &array_bounds(self), neighbourhood, |n| {
+            self.get_point(n) == Some(value)
+        }))
+    }
+
+    fn components(&self, neighbourhood: &Neighbourhood) -> Vec<Region> {
+        components_of(
+            self.indices_row_major()
+                .map(|(row, col)| Point::from_index(col, row)),
+            &array_bounds(self),
+            neighbourhood,
+            |p, n| self.get_point(n) == self.get_point(p),
+        )
+    }
+}
+
+fn array_bounds<T>(grid: &Array2D<T>) -> Bounds {
+    Bounds {
+        min_x: 0,
+        max_x: grid.num_columns() as i32 - 1,
+        min_y: 0,
+        max_y: grid.num_rows() as i32 - 1,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use itertools::Itertools;
+
+    fn grid(rows: &[&str]) -> Array2D<char> {
+        Array2D::from_rows(&rows.iter().map(|it| it.chars().collect()).collect_vec()).unwrap()
+    }
+
+    #[test]
+    fn measures_garden_plots() {
+        let g = grid(&["AAAA", "BBCD", "BBCC", "EEEC"]);
+        let regions = g.components(&Neighbourhood::Four);
+
+        let measured = regions
+            .iter()
+            .map(|it| (it.area(), it.perimeter(), it.sides()))
+            .collect_vec();
+        assert_eq!(
+            measured,
+            vec![(4, 10, 4), (4, 8, 4), (4, 10, 8), (1, 4, 4), (3, 8, 4)]
+        );
+    }
+
+    #[test]
+    fn counts_sides_around_holes() {
+        let g = grid(&["AAAAAA", "AAABBA", "AAABBA", "ABBAAA", "ABBAAA", "AAAAAA"]);
+        let a = g
+            .region_at(&Point::new(0, 0), &Neighbourhood::Four)
+            .unwrap();
+
+        assert_eq!(a.area(), 28);
+        assert_eq!(a.sides(), 12);
+    }
+
+    #[test]
+    fn connectivity_changes_components_of_sets() {
+        let points: HashSet<Point> = [Point::new(0, 0), Point::new(1, 1), Point::new(3, 3)]
+            .into_iter()
+            .collect();
+        let grid = BitSetGrid::from_hashset(&points);
+
+        assert_eq!(points.components(&Neighbourhood::Four).len(), 3);
+        assert_eq!(points.components(&Neighbourhood::Eight).len(), 2);
+        assert_eq!(grid.components(&Neighbourhood::Eight).len(), 2);
+        assert_eq!(
+            grid.region_at(&Point::new(1, 0), &Neighbourhood::Eight),
+            None
+        );
+
+        let bounds = grid.bounds().unwrap();
+        let open = flood_fill(&Point::new(1, 0), &bounds, &Neighbourhood::Four, |p| {
+            !grid.contains(p)
+        });
+        assert_eq!(open.area(), 16 - 3);
+        // Everything passes, the bounds alone end the fill
+        let all = flood_fill(
+            &Point::new(0, 0),
+            &bounds.expand(2),
+            &Neighbourhood::Eight,
+            |_| true,
+        );
+        assert_eq!(all.area(), 8 * 8);
+    }
+}
@@ -0,0 +1,724 @@
+use crate::util::{BitSetGrid, Bounds, GridAccess, Point};
+use array2d::Array2D;
+use rustc_hash::FxHashMap;
+use std::collections::HashSet;
+use std::fmt::{Display, Formatter};
+use std::hash::BuildHasher;
+use std::path::Path;
+use std::{fs, io};
+
+/// Anything that can be drawn as one character per cell.
+pub trait Cells {
+    fn cell_bounds(&self) -> Option<Bounds>;
+    fn glyph(&self, p: &Point) -> char;
+}
+
+impl Cells for Array2D<char> {
+    fn cell_bounds(&self) -> Option<Bounds> {
+        if self.num_elements() == 0 {
+            return None;
+        }
+        Some(Bounds {
+            min_x: 0,
+            max_x: self.num_columns() as i32 - 1,
+            min_y: 0,
+            max_y: self.num_rows() as i32 - 1,
+        })
+    }
+
+    fn glyph(&self, p: &Point) -> char {
+        *self.get_point(p).unwrap_or(&' ')
+    }
+}
+
+impl Cells for Array2D<bool> {
+    fn cell_bounds(&self) -> Option<Bounds> {
+        if self.num_elements() == 0 {
+            return None;
+        }
+        Some(Bounds {
+            min_x: 0,
+            max_x: self.num_columns() as i32 - 1,
+            min_y: 0,
+            max_y: self.num_rows() as i32 - 1,
+        })
+    }
+
+    fn glyph(&self, p: &Point) -> char {
+        if *self.get_point(p).unwrap_or(&false) {
+            '#'
+        } else {
+            '.'
+        }
+    }
+}
+
+impl Cells for BitSetGrid {
+    fn cell_bounds(&self) -> Option<Bounds> {
+        self.bounds()
+    }
+
+    fn glyph(&self, p: &Point) -> char {
+        if self.contains(p) { '#' } else { '.' }
+    }
+}
+
+impl<H: BuildHasher> Cells for HashSet<Point, H> {
+    fn cell_bounds(&self) -> Option<Bounds> {
+        Bounds::from_points(self)
+    }
+
+    fn glyph(&self, p: &Point) -> char {
+        if self.contains(p) { '#' } else { '.' }
+    }
+}
+
+impl Cells for Bounds {
+    fn cell_bounds(&self) -> Option<Bounds> {
+        Some(*self)
+    }
+
+    fn glyph(&self, _p: &Point) -> char {
+        '#'
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Colour {
+    Black,
+    White,
+    Grey,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    Rgb(u8, u8, u8),
+}
+
+impl Colour {
+    pub fn rgb(&self) -> [u8; 3] {
+        match self {
+            Colour::Black => [0, 0, 0],
+            Colour::White => [255, 255, 255],
+            Colour::Grey => [128, 128, 128],
+            Colour::Red => [220, 50, 47],
+            Colour::Green => [80, 200, 80],
+            Colour::Yellow => [240, 200, 40],
+            Colour::Blue => [40, 110, 220],
+            Colour::Magenta => [200, 60, 200],
+            Colour::Cyan => [40, 200, 220],
+            Colour::Rgb(r, g, b) => [*r, *g, *b],
+        }
+    }
+
+    fn ansi(&self) -> String {
+        match self {
+            Colour::Black => "30".to_string(),
+            Colour::Red => "31".to_string(),
+            Colour::Green => "32".to_string(),
+            Colour::Yellow => "33".to_string(),
+            Colour::Blue => "34".to_string(),
+            Colour::Magenta => "35".to_string(),
+            Colour::Cyan => "36".to_string(),
+            Colour::White => "37".to_string(),
+            Colour::Grey => "90".to_string(),
+            Colour::Rgb(r, g, b) => format!("38;2;{};{};{}", r, g, b),
+        }
+    }
+
+    fn svg(&self) -> String {
+        let [r, g, b] = self.rgb();
+        format!("#{:02x}{:02x}{:02x}", r, g, b)
+    }
+
+    /// Colours for the glyphs that keep coming up in puzzle inputs.
+    pub fn for_glyph(c: char) -> Colour {
+        match c {
+            '.' | ' ' => Colour::Black,
+            '#' => Colour::White,
+            '@' | 'O' => Colour::Yellow,
+            '^' | 'v' | '<' | '>' => Colour::Red,
+            'S' | 'E' => Colour::Green,
+            '|' | '-' => Colour::Cyan,
+            '0'..='9' => {
+                let v = (c as u8 - b'0') * 25 + 30;
+                Colour::Rgb(v, v, v)
+            }
+            _ => Colour::Grey,
+        }
+    }
+}
+
+/// Snapshot of a grid with optional highlighted cells.
+///
+/// Displays as text; highlighted cells are drawn as `*` unless ANSI colours are enabled.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    bounds: Option<Bounds>,
+    rows: Vec<Vec<char>>,
+    highlights: FxHashMap<Point, Colour>,
+    ansi: bool,
+}
+
+impl Frame {
+    pub fn new<G: Cells + ?Sized>(grid: &G) -> Frame {
+        let bounds = grid.cell_bounds();
+        let rows = bounds
+            .map(|b| {
+                b.ys()
+                    .map(|y| b.xs().map(|x| grid.glyph(&Point::new(x, y))).collect())
+                    .collect()
+            })
+            .unwrap_or_default();
+        Frame {
+            bounds,
+            rows,
+            highlights: FxHashMap::default(),
+            ansi: false,
+        }
+    }
+
+    pub fn highlight(mut self, points: impl IntoIterator<Item = Point>, colour: Colour) -> Frame {
+        self.highlights
+            .extend(points.into_iter().map(|p| (p, colour)));
+        self
+    }
+
+    pub fn ansi(mut self, ansi: bool) -> Frame {
+        self.ansi = ansi;
+        self
+    }
+
+    pub fn bounds(&self) -> Option<Bounds> {
+        self.bounds
+    }
+
+    pub fn print(&self) {
+        println!("{}", self);
+    }
+
+    /// Draws every cell as a `scale`×`scale` square coloured by `palette`.
+    pub fn to_image(&self, scale: usize, palette: impl Fn(char) -> Colour) -> Image {
+        let Some(bounds) = self.bounds else {
+            return Image::new(0, 0, Colour::Black);
+        };
+        let mut image = Image::new(
+            bounds.width() as usize * scale,
+            bounds.height() as usize * scale,
+            Colour::Black,
+        );
+        for (row, line) in self.rows.iter().enumerate() {
+            for (col, c) in line.iter().enumerate() {
+                let p = Point::new(bounds.min_x + col as i32, bounds.min_y + row as i32);
+                let colour = self
+                    .highlights
+                    .get(&p)
+                    .copied()
+                    .unwrap_or_else(|| palette(*c));
+                image.fill(col * scale, row * scale, scale, scale, colour);
+            }
+        }
+        image
+    }
+}
+
+impl Display for Frame {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let Some(bounds) = self.bounds else {
+            return Ok(());
+        };
+        for (row, line) in self.rows.iter().enumerate() {
+            if row > 0 {
+                writeln!(f)?;
+            }
+            for (col, c) in line.iter().enumerate() {
+                let p = Point::new(bounds.min_x + col as i32, bounds.min_y + row as i32);
+                match self.highlights.get(&p) {
+                    Some(colour) if self.ansi => {
+                        write!(f, "\x1b[1;{}m{}\x1b[0m", colour.ansi(), c)?
+                    }
+                    Some(_) => write!(f, "*")?,
+                    None => write!(f, "{}", c)?,
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+    width: usize,
+    height: usize,
+    pixels: Vec<[u8; 3]>,
+}
+
+impl Image {
+    pub fn new(width: usize, height: usize, background: Colour) -> Image {
+        Image {
+            width,
+            height,
+            pixels: vec![background.rgb(); width * height],
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<[u8; 3]> {
+        if x < self.width && y < self.height {
+            Some(self.pixels[y * self.width + x])
+        } else {
+            None
+        }
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, colour: Colour) {
+        if x < self.width && y < self.height {
+            self.pixels[y * self.width + x] = colour.rgb();
+        }
+    }
+
+    pub fn fill(&mut self, x: usize, y: usize, width: usize, height: usize, colour: Colour) {
+        for yy in y..(y + height).min(self.height) {
+            for xx in x..(x + width).min(self.width) {
+                self.pixels[yy * self.width + xx] = colour.rgb();
+            }
+        }
+    }
+
+    pub fn to_ppm(&self) -> Vec<u8> {
+        let mut out = format!("P6\n{} {}\n255\n", self.width, self.height).into_bytes();
+        out.extend(self.pixels.iter().flatten());
+        out
+    }
+
+    /// Encodes as an RGB PNG with uncompressed deflate blocks.
+    pub fn to_png(&self) -> Vec<u8> {
+        let mut raw = Vec::with_capacity((self.width * 3 + 1) * self.height);
+        for row in self.pixels.chunks(self.width.max(1)).take(self.height) {
+            raw.push(0);
+            raw.extend(row.iter().flatten());
+        }
+
+        let mut ihdr = vec![];
+        ihdr.extend((self.width as u32).to_be_bytes());
+        ihdr.extend((self.height as u32).to_be_bytes());
+        ihdr.extend([8, 2, 0, 0, 0]);
+
+        let mut out = vec![0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n'];
+        png_chunk(&mut out, b"IHDR", &ihdr);
+        png_chunk(&mut out, b"IDAT", &zlib_stored(&raw));
+        png_chunk(&mut out, b"IEND", &[]);
+        out
+    }
+
+    pub fn write_ppm(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        fs::write(path, self.to_ppm())
+    }
+
+    pub fn write_png(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        fs::write(path, self.to_png())
+    }
+}
+
+fn png_chunk(out: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    out.extend((data.len() as u32).to_be_bytes());
+    let start = out.len();
+    out.extend(kind);
+    out.extend(data);
+    let crc = crc32(&out[start..]);
+    out.extend(crc.to_be_bytes());
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xffff_ffffu32;
+    for b in data {
+        crc ^= *b as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xedb8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    let mut out = vec![0x78, 0x01];
+    let mut blocks = data.chunks(0xffff).peekable();
+    if blocks.peek().is_none() {
+        out.extend([1, 0, 0, 0xff, 0xff]);
+    }
+    while let Some(block) = blocks.next() {
+        out.push(if blocks.peek().is_none() { 1 } else { 0 });
+        out.extend((block.len() as u16).to_le_bytes());
+        out.extend((!(block.len() as u16)).to_le_bytes());
+        out.extend(block);
+    }
+    out.extend(adler32(data).to_be_bytes());
+    out
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for byte in data {
+        a = (a + *byte as u32) % 65521;
+        b = (b + a) % 65521;
+    }
+    (b << 16) | a
+}
+
+/// Encodes equally sized images as a looping GIF, showing each for `delay` hundredths of a second.
+///
+/// Fails for frames over 65535 pixels wide or high, or with more than 256 colours in total, as
+/// a GIF cannot describe those.
+pub fn encode_gif(frames: &[Image], delay: u16) -> io::Result<Vec<u8>> {
+    let (width, height) = frames.first().map_or((0, 0), |it| (it.width, it.height));
+    assert!(
+        frames
+            .iter()
+            .all(|it| it.width == width && it.height == height),
+        "All frames need the same size"
+    );
+    let invalid = |message: String| io::Error::new(io::ErrorKind::InvalidInput, message);
+    let too_large = |_| invalid(format!("{}x{} is too large for a GIF", width, height));
+    let (width, height) = (
+        u16::try_from(width).map_err(too_large)?,
+        u16::try_from(height).map_err(too_large)?,
+    );
+
+    let mut palette: Vec<[u8; 3]> = vec![];
+    let mut lookup: FxHashMap<[u8; 3], u8> = FxHashMap::default();
+    let mut indexed = vec![];
+    for frame in frames {
+        let mut pixels = Vec::with_capacity(frame.pixels.len());
+        for px in &frame.pixels {
+            let index = match lookup.get(px) {
+                Some(index) => *index,
+                None => {
+                    let index = u8::try_from(palette.len()).map_err(|_| {
+                        invalid("GIF frames can have at most 256 colours".to_string())
+                    })?;
+                    palette.push(*px);
+                    lookup.insert(*px, index);
+                    index
+                }
+            };
+            pixels.push(index);
+        }
+        indexed.push(pixels);
+    }
+    let bits = (1..=8).find(|b| 1 << b >= palette.len()).unwrap();
+    palette.resize(1 << bits, [0, 0, 0]);
+
+    let mut out = b"GIF89a".to_vec();
+    out.extend(width.to_le_bytes());
+    out.extend(height.to_le_bytes());
+    out.extend([0x80 | 0x70 | (bits - 1) as u8, 0, 0]);
+    out.extend(palette.iter().flatten());
+    out.extend(b"\x21\xff\x0bNETSCAPE2.0\x03\x01\x00\x00\x00");
+    for pixels in indexed {
+        out.extend([0x21, 0xf9, 0x04, 0x00]);
+        out.extend(delay.to_le_bytes());
+        out.extend([0x00, 0x00]);
+        out.push(0x2c);
+        out.extend([0, 0, 0, 0]);
+        out.extend(width.to_le_bytes());
+        out.extend(height.to_le_bytes());
+        out.push(0);
+        let min_code_size = bits.max(2) as u8;
+        out.push(min_code_size);
+        for block in lzw(&pixels, min_code_size).chunks(255) {
+            out.push(block.len() as u8);
+            out.extend(block);
+        }
+        out.push(0);
+    }
+    out.push(0x3b);
+    Ok(out)
+}
+
+fn lzw(pixels: &[u8], min_code_size: u8) -> Vec<u8> {
+    let clear = 1u16 << min_code_size;
+    let end = clear + 1;
+    let mut out = vec![];
+    let (mut acc, mut acc_bits) = (0u32, 0u32);
+    let mut code_size = min_code_size as u32 + 1;
+    let mut next = end + 1;
+    let mut dict: FxHashMap<(u16, u8), u16> = FxHashMap::default();
+
+    let mut emit = |code: u16, code_size: &mut u32, next: u16| {
+        acc |= (code as u32) << acc_bits;
+        acc_bits += *code_size;
+        while acc_bits >= 8 {
+            out.push(acc as u8);
+            acc >>= 8;
+            acc_bits -= 8;
+        }
+        if next >= 1 << *code_size && *code_size < 12 {
+            *code_size += 1;
+        }
+    };
+
+    emit(clear, &mut code_size, next);
+    let Some((first, rest)) = pixels.split_first() else {
+        emit(end, &mut code_size, next);
+        if acc_bits > 0 {
+            out.push(acc as u8);
+        }
+        return out;
+    };
+    let mut current = *first as u16;
+    for px in rest {
+        if let Some(code) = dict.get(&(current, *px)) {
+            current = *code;
+            continue;
+        }
+        emit(current, &mut code_size, next);
+        if next >= 4095 {
+            emit(clear, &mut code_size, next);
+            dict.clear();
+            code_size = min_code_size as u32 + 1;
+            next = end + 1;
+        } else {
+            dict.insert((current, *px), next);
+            next += 1;
+        }
+        current = *px as u16;
+    }
+    emit(current, &mut code_size, next);
+    emit(end, &mut code_size, next);
+    if acc_bits > 0 {
+        out.push(acc as u8);
+    }
+    out
+}
+
+/// Collects shapes in puzzle coordinates and scales them to fit the picture.
+#[derive(Debug, Clone, Default)]
+pub struct Svg {
+    elements: Vec<String>,
+    bounds: Option<(i64, i64, i64, i64)>,
+}
+
+impl Svg {
+    pub fn new() -> Svg {
+        Svg::default()
+    }
+
+    fn include(&mut self, x: i64, y: i64) {
+        self.bounds = Some(match self.bounds {
+            None => (x, x, y, y),
+            Some((x0, x1, y0, y1)) => (x0.min(x), x1.max(x), y0.min(y), y1.max(y)),
+        });
+    }
+
+    fn points_attr(&mut self, points: impl IntoIterator<Item = (i64, i64)>) -> String {
+        points
+            .into_iter()
+            .map(|(x, y)| {
+                self.include(x, y);
+                format!("{},{}", x, y)
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Closed polygon through `points`, e.g. a loop of red tiles.
+    pub fn polygon(
+        &mut self,
+        points: impl IntoIterator<Item = (i64, i64)>,
+        fill: Colour,
+        stroke: Colour,
+    ) -> &mut Svg {
+        let points = self.points_attr(points);
+        self.elements.push(format!(
+            r#"<polygon points="{}" fill="{}" stroke="{}" stroke-width="1" vector-effect="non-scaling-stroke"/>"#,
+            points,
+            fill.svg(),
+            stroke.svg()
+        ));
+        self
+    }
+
+    pub fn polyline(
+        &mut self,
+        points: impl IntoIterator<Item = (i64, i64)>,
+        stroke: Colour,
+    ) -> &mut Svg {
+        let points = self.points_attr(points);
+        self.elements.push(format!(
+            r#"<polyline points="{}" fill="none" stroke="{}" stroke-width="1" vector-effect="non-scaling-stroke"/>"#,
+            points,
+            stroke.svg()
+        ));
+        self
+    }
+
+    /// Rectangle spanned by two opposite corners.
+    pub fn rect(&mut self, a: (i64, i64), b: (i64, i64), fill: Colour, opacity: f64) -> &mut Svg {
+        self.include(a.0, a.1);
+        self.include(b.0, b.1);
+        self.elements.push(format!(
+            r#"<rect x="{}" y="{}" width="{}" height="{}" fill="{}" fill-opacity="{}"/>"#,
+            a.0.min(b.0),
+            a.1.min(b.1),
+            (a.0 - b.0).abs(),
+            (a.1 - b.1).abs(),
+            fill.svg(),
+            opacity
+        ));
+        self
+    }
+
+    pub fn point(&mut self, p: (i64, i64), colour: Colour) -> &mut Svg {
+        self.include(p.0, p.1);
+        self.elements.push(format!(
+            r#"<circle cx="{}" cy="{}" r="3" fill="{}" vector-effect="non-scaling-stroke" transform-origin="{} {}"/>"#,
+            p.0,
+            p.1,
+            colour.svg(),
+            p.0,
+            p.1
+        ));
+        self
+    }
+
+    pub fn write(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        fs::write(path, self.to_string())
+    }
+}
+
+impl Display for Svg {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let (x0, x1, y0, y1) = self.bounds.unwrap_or((0, 0, 0, 0));
+        let margin = ((x1 - x0).max(y1 - y0) / 50).max(1);
+        writeln!(
+            f,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="{} {} {} {}" width="1000" height="1000" style="background:black">"#,
+            x0 - margin,
+            y0 - margin,
+            x1 - x0 + 2 * margin,
+            y1 - y0 + 2 * margin
+        )?;
+        for element in &self.elements {
+            writeln!(f, "  {}", element)?;
+        }
+        write!(f, "</svg>")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_point_sets_with_highlights() {
+        let points: HashSet<Point> = [Point::new(-1, 0), Point::new(1, 1)].into_iter().collect();
+
+        assert_eq!(Frame::new(&points).to_string(), "#..\n..#");
+        let frame = Frame::new(&points).highlight([Point::new(0, 1)], Colour::Red);
+        assert_eq!(frame.to_string(), "#..\n.*#");
+        assert_eq!(frame.ansi(true).to_string(), "#..\n.\x1b[1;31m.\x1b[0m#");
+    }
+
+    #[test]
+    fn png_matches_reference_bytes() {
+        assert_eq!(crc32(b"123456789"), 0xcbf4_3926);
+        assert_eq!(adler32(b"Wikipedia"), 0x11e6_0398);
+
+        let mut image = Image::new(2, 2, Colour::Black);
+        image.set(0, 0, Colour::Rgb(255, 0, 0));
+        image.set(1, 1, Colour::White);
+        // Built independently with Python's struct and zlib.compress(raw, 0)
+        let expected = [
+            &b"\x89PNG\r\n\x1a\n"[..],
+            b"\0\0\0\x0dIHDR\0\0\0\x02\0\0\0\x02\x08\x02\0\0\0\xfd\xd4\x9a\x73",
+            b"\0\0\0\x19IDAT\x78\x01\x01\x0e\0\xf1\xff",
+            b"\0\xff\0\0\0\0\0\0\0\0\0\xff\xff\xff\x12\xfb\x03\xfd\x92\xc6\xdd\x9e",
+            b"\0\0\0\0IEND\xae\x42\x60\x82",
+        ]
+        .concat();
+        assert_eq!(image.to_png(), expected);
+        assert_eq!(&image.to_ppm()[..11], b"P6\n2 2\n255\n");
+    }
+
+    #[test]
+    fn lzw_matches_reference_bytes() {
+        // The 10x10 sample image from "What's in a GIF", with its published image data
+        let pixels = [
+            "1111122222",
+            "1111122222",
+            "1111122222",
+            "1110000222",
+            "1110000222",
+            "2220000111",
+            "2220000111",
+            "2222211111",
+            "2222211111",
+            "2222211111",
+        ]
+        .iter()
+        .flat_map(|row| row.bytes().map(|it| it - b'0'))
+        .collect::<Vec<_>>();
+        assert_eq!(
+            lzw(&pixels, 2),
+            [
+                0x8c, 0x2d, 0x99, 0x87, 0x2a, 0x1c, 0xdc, 0x33, 0xa0, 0x02, 0x75, 0xec, 0x95, 0xfa,
+                0xa8, 0xde, 0x60, 0x8c, 0x04, 0x91, 0x4c, 0x01
+            ]
+        );
+    }
+
+    #[test]
+    fn gif_matches_reference_bytes() {
+        let mut image = Image::new(2, 1, Colour::Black);
+        image.set(1, 0, Colour::White);
+        // Codes clear, 0, 1, end at 3 bits each, packed LSB first: 100 000 001 101
+        let expected = [
+            &b"GIF89a\x02\0\x01\0\xf0\0\0"[..],
+            b"\0\0\0\xff\xff\xff",
+            b"\x21\xff\x0bNETSCAPE2.0\x03\x01\0\0\0",
+            b"\x21\xf9\x04\0\x0a\0\0\0",
+            b"\x2c\0\0\0\0\x02\0\x01\0\0",
+            b"\x02\x02\x44\x0a\0",
+            b"\x3b",
+        ]
+        .concat();
+        assert_eq!(encode_gif(&[image], 10).unwrap(), expected);
+
+        let wide = Image::new(70_000, 1, Colour::Black);
+        assert!(encode_gif(&[wide], 10).is_err());
+        let mut colourful = Image::new(257, 1, Colour::Black);
+        for x in 0..257 {
+            colourful.set(x, 0, Colour::Rgb((x % 256) as u8, (x / 256) as u8, 0));
+        }
+        assert!(encode_gif(&[colourful], 10).is_err());
+    }
+
+    #[test]
+    fn svg_fits_the_view_box_around_all_shapes() {
+        let mut svg = Svg::new();
+        svg.polygon([(0, 0), (10, 0), (10, 5)], Colour::Green, Colour::Red)
+            .point((2, 3), Colour::Rgb(1, 2, 3));
+
+        let expected = [
+            r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="-1 -1 12 7" width="1000" height="1000" style="background:black">"#,
+            r##"  <polygon points="0,0 10,0 10,5" fill="#50c850" stroke="#dc322f" stroke-width="1" vector-effect="non-scaling-stroke"/>"##,
+            r##"  <circle cx="2" cy="3" r="3" fill="#010203" vector-effect="non-scaling-stroke" transform-origin="2 3"/>"##,
+            "</svg>",
+        ]
+        .join("\n");
+        assert_eq!(svg.to_string(), expected);
+    }
+}
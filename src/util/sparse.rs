@@ -0,0 +1,322 @@
+use crate::util::automaton::Neighbourhood;
+use crate::util::render::{Cells, Frame};
+use crate::util::{BitSetGrid, Bounds, Point};
+use array2d::Array2D;
+use rustc_hash::FxHashMap;
+use std::collections::{BTreeMap, HashSet};
+use std::fmt::{Display, Formatter};
+use std::hash::BuildHasher;
+
+/// Grid of unknown extent, storing only the cells that were set.
+///
+/// The bounding box is kept up to date on every insert and remove by counting the cells in each
+/// row and column, so `bounds()` never has to look at all cells.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SparseGrid<T> {
+    cells: FxHashMap<Point, T>,
+    columns: BTreeMap<i32, usize>,
+    rows: BTreeMap<i32, usize>,
+}
+
+impl<T> Default for SparseGrid<T> {
+    fn default() -> Self {
+        SparseGrid {
+            cells: FxHashMap::default(),
+            columns: BTreeMap::new(),
+            rows: BTreeMap::new(),
+        }
+    }
+}
+
+impl<T> FromIterator<(Point, T)> for SparseGrid<T> {
+    fn from_iter<I: IntoIterator<Item = (Point, T)>>(iter: I) -> Self {
+        let mut grid = SparseGrid::new();
+        grid.extend(iter);
+        grid
+    }
+}
+
+impl<T> Extend<(Point, T)> for SparseGrid<T> {
+    fn extend<I: IntoIterator<Item = (Point, T)>>(&mut self, iter: I) {
+        for (p, v) in iter {
+            self.insert(p, v);
+        }
+    }
+}
+
+impl<T> SparseGrid<T> {
+    pub fn new() -> SparseGrid<T> {
+        SparseGrid::default()
+    }
+
+    /// Sets `p` to `value`, returning the previous value.
+    pub fn insert(&mut self, p: Point, value: T) -> Option<T> {
+        let previous = self.cells.insert(p, value);
+        if previous.is_none() {
+            *self.columns.entry(p.x).or_default() += 1;
+            *self.rows.entry(p.y).or_default() += 1;
+        }
+        previous
+    }
+
+    pub fn remove(&mut self, p: &Point) -> Option<T> {
+        let previous = self.cells.remove(p)?;
+        release(&mut self.columns, p.x);
+        release(&mut self.rows, p.y);
+        Some(previous)
+    }
+
+    pub fn get(&self, p: &Point) -> Option<&T> {
+        self.cells.get(p)
+    }
+
+    pub fn get_mut(&mut self, p: &Point) -> Option<&mut T> {
+        self.cells.get_mut(p)
+    }
+
+    /// The value at `p`, or `default` for cells that were never set.
+    pub fn get_or<'a>(&'a self, p: &Point, default: &'a T) -> &'a T {
+        self.cells.get(p).unwrap_or(default)
+    }
+
+    /// The value at `p`, inserting `T::default()` first if it was never set.
+    pub fn entry(&mut self, p: Point) -> &mut T
+    where
+        T: Default,
+    {
+        if !self.cells.contains_key(&p) {
+            self.insert(p, T::default());
+        }
+        self.cells.get_mut(&p).unwrap()
+    }
+
+    pub fn contains(&self, p: &Point) -> bool {
+        self.cells.contains_key(p)
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// Smallest bounds containing every set cell, or `None` if there are none.
+    pub fn bounds(&self) -> Option<Bounds> {
+        Some(Bounds {
+            min_x: *self.columns.first_key_value()?.0,
+            max_x: *self.columns.last_key_value()?.0,
+            min_y: *self.rows.first_key_value()?.0,
+            max_y: *self.rows.last_key_value()?.0,
+        })
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&Point, &T)> {
+        self.cells.iter()
+    }
+
+    pub fn points(&self) -> impl Iterator<Item = &Point> {
+        self.cells.keys()
+    }
+
+    /// The set neighbours of `p`, in the order of `neighbourhood.offsets()`.
+    pub fn neighbours<'a>(
+        &'a self,
+        p: &Point,
+        neighbourhood: &Neighbourhood,
+    ) -> impl Iterator<Item = (Point, &'a T)> + use<'a, T> {
+        let p = *p;
+        neighbourhood
+            .offsets()
+            .into_iter()
+            .map(move |(dx, dy)| Point::new(p.x + dx, p.y + dy))
+            .filter_map(|n| self.cells.get(&n).map(|v| (n, v)))
+    }
+
+    /// Sets every point of `points` to a clone of `value`.
+    pub fn from_points(points: impl IntoIterator<Item = Point>, value: T) -> SparseGrid<T>
+    where
+        T: Clone,
+    {
+        points.into_iter().map(|p| (p, value.clone())).collect()
+    }
+
+    /// Cells of `grid` for which `keep` holds, with `x` as the column.
+    pub fn from_array2d(grid: &Array2D<T>, keep: impl Fn(&T) -> bool) -> SparseGrid<T>
+    where
+        T: Clone,
+    {
+        grid.enumerate_row_major()
+            .filter(|(_, v)| keep(v))
+            .map(|((row, col), v)| (Point::from_index(col, row), v.clone()))
+            .collect()
+    }
+
+    /// Copies `bounds()` into an `Array2D`, filling unset cells with `default`.
+    ///
+    /// The top left corner of the bounds becomes `(0, 0)`.
+    pub fn to_array2d(&self, default: T) -> Array2D<T>
+    where
+        T: Clone,
+    {
+        let Some(b) = self.bounds() else {
+            return Array2D::from_row_major(&[], 0, 0).unwrap();
+        };
+        let mut grid = Array2D::filled_with(default, b.height() as usize, b.width() as usize);
+        for (p, v) in &self.cells {
+            grid[((p.y - b.min_y) as usize, (p.x - b.min_x) as usize)] = v.clone();
+        }
+        grid
+    }
+
+    pub fn to_hashset(&self) -> HashSet<Point> {
+        self.cells.keys().copied().collect()
+    }
+
+    /// Set cells as a `BitSetGrid` covering `bounds()`.
+    pub fn to_bit_set_grid(&self) -> BitSetGrid {
+        let mut grid = match self.bounds() {
+            Some(b) => BitSetGrid::with_bounds(b),
+            None => BitSetGrid::new(0, 0),
+        };
+        grid.extend(self.cells.keys().copied());
+        grid
+    }
+
+    /// Draws every cell of `bounds()` with `glyph`, which gets `None` for unset cells.
+    pub fn frame(&self, glyph: impl Fn(Option<&T>) -> char) -> Frame {
+        Frame::new(&Glyphs { grid: self, glyph })
+    }
+}
+
+impl SparseGrid<bool> {
+    pub fn from_hashset<H: BuildHasher>(col: &HashSet<Point, H>) -> SparseGrid<bool> {
+        SparseGrid::from_points(col.iter().copied(), true)
+    }
+
+    pub fn from_bit_set_grid(grid: &BitSetGrid) -> SparseGrid<bool> {
+        SparseGrid::from_points(grid, true)
+    }
+}
+
+fn release(counts: &mut BTreeMap<i32, usize>, key: i32) {
+    let count = counts.get_mut(&key).unwrap();
+    *count -= 1;
+    if *count == 0 {
+        counts.remove(&key);
+    }
+}
+
+struct Glyphs<'a, T, F> {
+    grid: &'a SparseGrid<T>,
+    glyph: F,
+}
+
+impl<T, F: Fn(Option<&T>) -> char> Cells for Glyphs<'_, T, F> {
+    fn cell_bounds(&self) -> Option<Bounds> {
+        self.grid.bounds()
+    }
+
+    fn glyph(&self, p: &Point) -> char {
+        (self.glyph)(self.grid.get(p))
+    }
+}
+
+impl Cells for SparseGrid<char> {
+    fn cell_bounds(&self) -> Option<Bounds> {
+        self.bounds()
+    }
+
+    fn glyph(&self, p: &Point) -> char {
+        *self.get_or(p, &'.')
+    }
+}
+
+impl Cells for SparseGrid<bool> {
+    fn cell_bounds(&self) -> Option<Bounds> {
+        self.bounds()
+    }
+
+    fn glyph(&self, p: &Point) -> char {
+        if *self.get_or(p, &false) { '#' } else { '.' }
+    }
+}
+
+impl Display for SparseGrid<char> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", Frame::new(self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_bounds_up_to_date() {
+        let mut grid = SparseGrid::new();
+        assert_eq!(grid.bounds(), None);
+
+        grid.insert(Point::new(2, -3), 'a');
+        grid.insert(Point::new(-4, 5), 'b');
+        grid.insert(Point::new(2, 5), 'c');
+        assert_eq!(
+            grid.bounds(),
+            Bounds::from_points([Point::new(-4, -3), Point::new(2, 5)])
+        );
+
+        grid.remove(&Point::new(-4, 5));
+        assert_eq!(
+            grid.bounds(),
+            Bounds::from_points([Point::new(2, -3), Point::new(2, 5)])
+        );
+        grid.insert(Point::new(2, 5), 'd');
+        grid.remove(&Point::new(2, 5));
+        assert_eq!(grid.bounds(), Bounds::from_points([Point::new(2, -3)]));
+        assert_eq!(grid.remove(&Point::new(2, 5)), None);
+        grid.remove(&Point::new(2, -3));
+        assert_eq!(grid.bounds(), None);
+        assert!(grid.is_empty());
+    }
+
+    #[test]
+    fn reads_defaults_and_neighbours() {
+        let mut grid: SparseGrid<u32> = SparseGrid::new();
+        *grid.entry(Point::new(0, 0)) += 2;
+        *grid.entry(Point::new(0, 0)) += 3;
+        grid.insert(Point::new(1, 1), 7);
+
+        assert_eq!(grid.get(&Point::new(0, 0)), Some(&5));
+        assert_eq!(*grid.get_or(&Point::new(9, 9), &0), 0);
+        let around = |p, n| grid.neighbours(&p, n).collect::<Vec<_>>();
+        assert_eq!(around(Point::new(0, 1), &Neighbourhood::Four).len(), 2);
+        assert_eq!(around(Point::new(-1, -1), &Neighbourhood::Four), vec![]);
+        assert_eq!(
+            around(Point::new(-1, -1), &Neighbourhood::Eight),
+            vec![(Point::new(0, 0), &5)]
+        );
+        assert_eq!(
+            grid.frame(|v| v.map_or('.', |it| char::from_digit(*it, 10).unwrap()))
+                .to_string(),
+            "5.\n.7"
+        );
+    }
+
+    #[test]
+    fn converts_between_grid_types() {
+        let rows = vec![vec!['#', '.', '.'], vec!['.', '.', '#']];
+        let array = Array2D::from_rows(&rows).unwrap();
+        let sparse = SparseGrid::from_array2d(&array, |c| *c == '#');
+
+        assert_eq!(sparse.len(), 2);
+        assert_eq!(sparse.to_array2d('.'), array);
+        assert_eq!(sparse.to_string(), "#..\n..#");
+
+        let set = sparse.to_hashset();
+        let bits = SparseGrid::from_hashset(&set).to_bit_set_grid();
+        assert_eq!(bits.to_hashset(), set);
+        assert_eq!(bits.bounds(), sparse.bounds());
+        assert_eq!(SparseGrid::from_bit_set_grid(&bits).to_hashset(), set);
+    }
+}
@@ -0,0 +1,256 @@
+use crate::util::{BitSetGrid, Bounds, Point};
+use array2d::Array2D;
+
+/// Rectangular grid that can be read cell by cell and rebuilt from a function.
+///
+/// `Array2D` always starts at `<0, 0>` (`x` being the column), `BitSetGrid` keeps its origin.
+pub trait Grid: Sized {
+    type Cell: Clone;
+
+    /// Covered area; `max` is below `min` for an empty grid.
+    fn grid_bounds(&self) -> Bounds;
+    fn cell(&self, p: &Point) -> Self::Cell;
+    fn from_fn(bounds: Bounds, f: impl FnMut(Point) -> Self::Cell) -> Self;
+}
+
+impl<T: Clone> Grid for Array2D<T> {
+    type Cell = T;
+
+    fn grid_bounds(&self) -> Bounds {
+        Bounds {
+            min_x: 0,
+            max_x: self.num_columns() as i32 - 1,
+            min_y: 0,
+            max_y: self.num_rows() as i32 - 1,
+        }
+    }
+
+    fn cell(&self, p: &Point) -> T {
+        self[(p.y as usize, p.x as usize)].clone()
+    }
+
+    fn from_fn(bounds: Bounds, mut f: impl FnMut(Point) -> T) -> Self {
+        let rows = bounds
+            .ys()
+            .map(|y| bounds.xs().map(|x| f(Point::new(x, y))).collect())
+            .collect::<Vec<Vec<T>>>();
+        if rows.is_empty() || rows[0].is_empty() {
+            return Array2D::from_row_major(&[], rows.len(), 0).unwrap();
+        }
+        Array2D::from_rows(&rows).unwrap()
+    }
+}
+
+impl Grid for BitSetGrid {
+    type Cell = bool;
+
+    fn grid_bounds(&self) -> Bounds {
+        self.bounds().unwrap_or(Bounds {
+            min_x: 0,
+            max_x: -1,
+            min_y: 0,
+            max_y: -1,
+        })
+    }
+
+    fn cell(&self, p: &Point) -> bool {
+        self.contains(p)
+    }
+
+    fn from_fn(bounds: Bounds, mut f: impl FnMut(Point) -> bool) -> Self {
+        let mut grid = BitSetGrid::with_bounds(bounds);
+        for y in bounds.ys() {
+            for x in bounds.xs() {
+                let p = Point::new(x, y);
+                if f(p) {
+                    grid.insert(&p);
+                }
+            }
+        }
+        grid
+    }
+}
+
+/// Rotations, flips and resizing for any `Grid`. Results keep the top left corner in place.
+pub trait Transform: Grid {
+    /// Rotates clockwise by `quarter_turns` × 90°; negative values rotate counter-clockwise.
+    fn rotate(&self, quarter_turns: i32) -> Self {
+        let b = self.grid_bounds();
+        let (w, h) = (b.width(), b.height());
+        let rotated = |w: i32, h: i32| Bounds {
+            max_x: b.min_x + w - 1,
+            max_y: b.min_y + h - 1,
+            ..b
+        };
+        match quarter_turns.rem_euclid(4) {
+            0 => Self::from_fn(b, |p| self.cell(&p)),
+            1 => Self::from_fn(rotated(h, w), |p| {
+                let (x, y) = (p.x - b.min_x, p.y - b.min_y);
+                self.cell(&Point::new(b.min_x + y, b.min_y + h - 1 - x))
+            }),
+            2 => Self::from_fn(b, |p| {
+                let (x, y) = (p.x - b.min_x, p.y - b.min_y);
+                self.cell(&Point::new(b.min_x + w - 1 - x, b.min_y + h - 1 - y))
+            }),
+            _ => Self::from_fn(rotated(h, w), |p| {
+                let (x, y) = (p.x - b.min_x, p.y - b.min_y);
+                self.cell(&Point::new(b.min_x + w - 1 - y, b.min_y + x))
+            }),
+        }
+    }
+
+    fn rotate_cw(&self) -> Self {
+        self.rotate(1)
+    }
+
+    fn rotate_ccw(&self) -> Self {
+        self.rotate(-1)
+    }
+
+    /// Mirrors left to right.
+    fn flip_horizontal(&self) -> Self {
+        let b = self.grid_bounds();
+        Self::from_fn(b, |p| self.cell(&Point::new(b.min_x + b.max_x - p.x, p.y)))
+    }
+
+    /// Mirrors top to bottom.
+    fn flip_vertical(&self) -> Self {
+        let b = self.grid_bounds();
+        Self::from_fn(b, |p| self.cell(&Point::new(p.x, b.min_y + b.max_y - p.y)))
+    }
+
+    /// Swaps rows and columns.
+    fn transpose(&self) -> Self {
+        let b = self.grid_bounds();
+        let transposed = Bounds {
+            max_x: b.min_x + b.height() - 1,
+            max_y: b.min_y + b.width() - 1,
+            ..b
+        };
+        Self::from_fn(transposed, |p| {
+            self.cell(&Point::new(
+                b.min_x + p.y - b.min_y,
+                b.min_y + p.x - b.min_x,
+            ))
+        })
+    }
+
+    /// All 8 rotations and reflections, starting with the grid itself.
+    fn symmetries(&self) -> Vec<Self> {
+        let flipped = self.flip_horizontal();
+        (0..4)
+            .map(|it| self.rotate(it))
+            .chain((0..4).map(|it| flipped.rotate(it)))
+            .collect()
+    }
+
+    /// The part of the grid inside `bounds`. For `Array2D` it starts at `<0, 0>` again.
+    fn crop(&self, bounds: &Bounds) -> Self {
+        match self.grid_bounds().intersect(bounds) {
+            Some(b) => Self::from_fn(b, |p| self.cell(&p)),
+            None => Self::from_fn(
+                Bounds {
+                    max_x: bounds.min_x - 1,
+                    max_y: bounds.min_y - 1,
+                    ..*bounds
+                },
+                |p| self.cell(&p),
+            ),
+        }
+    }
+
+    /// Adds `n` cells of `fill` on every side.
+    fn pad(&self, n: i32, fill: Self::Cell) -> Self {
+        let b = self.grid_bounds();
+        Self::from_fn(b.expand(n), |p| {
+            if b.contains(&p) {
+                self.cell(&p)
+            } else {
+                fill.clone()
+            }
+        })
+    }
+
+    /// Infinite view repeating the grid in every direction. Panics for an empty grid, which has
+    /// nothing to repeat.
+    fn tiled(&self) -> Tiled<'_, Self> {
+        let b = self.grid_bounds();
+        assert!(b.width() > 0 && b.height() > 0, "Cannot tile an empty grid");
+        Tiled { grid: self }
+    }
+}
+
+impl<G: Grid> Transform for G {}
+
+pub struct Tiled<'a, G> {
+    grid: &'a G,
+}
+
+impl<G: Grid> Tiled<'_, G> {
+    pub fn get(&self, p: &Point) -> G::Cell {
+        let b = self.grid.grid_bounds();
+        self.grid.cell(&Point::new(
+            b.min_x + (p.x - b.min_x).rem_euclid(b.width()),
+            b.min_y + (p.y - b.min_y).rem_euclid(b.height()),
+        ))
+    }
+
+    /// Copies the part of the view inside `bounds` into a grid of its own.
+    pub fn to_grid(&self, bounds: Bounds) -> G {
+        G::from_fn(bounds, |p| self.get(&p))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use itertools::Itertools;
+
+    fn grid(rows: &[&str]) -> Array2D<char> {
+        Array2D::from_rows(&rows.iter().map(|it| it.chars().collect()).collect_vec()).unwrap()
+    }
+
+    #[test]
+    fn rotates_and_flips() {
+        let g = grid(&["abc", "def"]);
+
+        assert_eq!(g.rotate_cw(), grid(&["da", "eb", "fc"]));
+        assert_eq!(g.rotate_ccw(), grid(&["cf", "be", "ad"]));
+        assert_eq!(g.rotate(2), grid(&["fed", "cba"]));
+        assert_eq!(g.rotate(4), g);
+        assert_eq!(g.flip_horizontal(), grid(&["cba", "fed"]));
+        assert_eq!(g.flip_vertical(), grid(&["def", "abc"]));
+        assert_eq!(g.transpose(), grid(&["ad", "be", "cf"]));
+        assert_eq!(g.symmetries().into_iter().unique().count(), 8);
+    }
+
+    #[test]
+    fn crops_pads_and_tiles() {
+        let g = grid(&["abc", "def"]);
+        let b = Bounds::from_points([Point::new(1, 0), Point::new(5, 1)]).unwrap();
+
+        assert_eq!(g.crop(&b), grid(&["bc", "ef"]));
+        assert_eq!(g.pad(1, '.'), grid(&[".....", ".abc.", ".def.", "....."]));
+        assert_eq!(g.tiled().get(&Point::new(-1, -1)), 'f');
+        assert_eq!(g.tiled().to_grid(b), grid(&["bcabc", "efdef"]));
+    }
+
+    #[test]
+    fn transforms_bit_set_grids_in_place() {
+        let g: BitSetGrid = [Point::new(10, 20), Point::new(11, 20), Point::new(10, 22)]
+            .into_iter()
+            .collect();
+
+        let rotated = g.rotate_cw();
+        assert_eq!(rotated.bounds().unwrap().min_x, 10);
+        assert_eq!(rotated.bounds().unwrap().width(), 3);
+        assert!(rotated.contains(&Point::new(12, 20)));
+        assert!(rotated.contains(&Point::new(12, 21)));
+        assert!(rotated.contains(&Point::new(10, 20)));
+        assert_eq!(
+            g.transpose().to_hashset(),
+            rotated.flip_horizontal().to_hashset()
+        );
+        assert_eq!(g.pad(2, false).bounds(), g.bounds().map(|it| it.expand(2)));
+    }
+}
@@ -0,0 +1,216 @@
+use itertools::Itertools;
+
+/// Disjoint sets over `0..n`, with path compression and union by size.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnionFind {
+    parent: Vec<usize>,
+    size: Vec<usize>,
+    components: usize,
+}
+
+impl UnionFind {
+    /// `n` singleton components.
+    pub fn new(n: usize) -> UnionFind {
+        UnionFind {
+            parent: (0..n).collect(),
+            size: vec![1; n],
+            components: n,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.parent.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parent.is_empty()
+    }
+
+    /// Representative of the component containing `x`, shortening the path on the way.
+    pub fn find(&mut self, x: usize) -> usize {
+        let root = self.root(x);
+        let mut x = x;
+        while self.parent[x] != root {
+            x = std::mem::replace(&mut self.parent[x], root);
+        }
+        root
+    }
+
+    /// Like `find`, but without compressing the path.
+    pub fn root(&self, mut x: usize) -> usize {
+        while self.parent[x] != x {
+            x = self.parent[x];
+        }
+        x
+    }
+
+    /// Merges the components of `a` and `b`. Returns `false` if they already were the same.
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (a, b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+        let (big, small) = if self.size[a] >= self.size[b] {
+            (a, b)
+        } else {
+            (b, a)
+        };
+        self.parent[small] = big;
+        self.size[big] += self.size[small];
+        self.components -= 1;
+        true
+    }
+
+    pub fn connected(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    pub fn component_count(&self) -> usize {
+        self.components
+    }
+
+    /// Size of the component containing `x`.
+    pub fn size_of(&mut self, x: usize) -> usize {
+        let root = self.find(x);
+        self.size[root]
+    }
+
+    /// Size of every component, singletons included, in no particular order.
+    pub fn component_sizes(&self) -> Vec<usize> {
+        (0..self.len())
+            .filter(|it| self.parent[*it] == *it)
+            .map(|it| self.size[it])
+            .collect()
+    }
+
+    /// Sizes of the `k` largest components, largest first.
+    pub fn largest(&self, k: usize) -> Vec<usize> {
+        self.component_sizes()
+            .into_iter()
+            .sorted_unstable_by(|a, b| b.cmp(a))
+            .take(k)
+            .collect()
+    }
+
+    /// Members of the component containing `x`, in ascending order.
+    pub fn members(&self, x: usize) -> Vec<usize> {
+        let root = self.root(x);
+        (0..self.len())
+            .filter(|it| self.root(*it) == root)
+            .collect()
+    }
+
+    /// All components with their members in ascending order, ordered by their smallest member.
+    pub fn components(&self) -> Vec<Vec<usize>> {
+        let mut by_root = vec![vec![]; self.len()];
+        for x in 0..self.len() {
+            by_root[self.root(x)].push(x);
+        }
+        let mut components = by_root
+            .into_iter()
+            .filter(|it| !it.is_empty())
+            .collect_vec();
+        components.sort_unstable_by_key(|it| it[0]);
+        components
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bit_set::BitSet;
+
+    /// The cluster bookkeeping day 8 used before, which only tracks non-singleton clusters.
+    fn bit_set_clusters(pairs: &[(usize, usize)]) -> Vec<BitSet> {
+        let mut clusters: Vec<BitSet> = vec![];
+        for (i, j) in pairs.iter().copied() {
+            let pos_i = clusters.iter().position(|it| it.contains(i));
+            let pos_j = clusters.iter().position(|it| it.contains(j));
+            match (pos_i, pos_j) {
+                (Some(a), Some(b)) if a == b => {}
+                (Some(a), Some(b)) => {
+                    let copy = clusters[b].clone();
+                    clusters[a].union_with(&copy);
+                    clusters.remove(b);
+                }
+                (Some(a), None) => {
+                    clusters[a].insert(j);
+                }
+                (None, Some(b)) => {
+                    clusters[b].insert(i);
+                }
+                (None, None) => clusters.push(BitSet::from_iter([i, j])),
+            }
+        }
+        clusters
+    }
+
+    fn pseudo_random_pairs(n: usize, count: usize) -> Vec<(usize, usize)> {
+        let mut state = 12345u64;
+        let mut next = || {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (state >> 33) as usize % n
+        };
+        (0..count)
+            .map(|_| (next(), next()))
+            .filter(|(a, b)| a != b)
+            .collect()
+    }
+
+    #[test]
+    fn matches_bit_set_clusters() {
+        let n = 60;
+        for count in [0, 10, 30, 60, 200] {
+            let pairs = pseudo_random_pairs(n, count);
+            let mut uf = UnionFind::new(n);
+            for (a, b) in &pairs {
+                uf.union(*a, *b);
+            }
+            let clusters = bit_set_clusters(&pairs);
+
+            let expected = clusters
+                .iter()
+                .map(|it| it.iter().collect_vec())
+                .sorted()
+                .collect_vec();
+            let actual = uf
+                .components()
+                .into_iter()
+                .filter(|it| it.len() > 1)
+                .collect_vec();
+            assert_eq!(actual, expected);
+            let singletons = n - clusters.iter().map(|it| it.len()).sum::<usize>();
+            assert_eq!(uf.component_count(), clusters.len() + singletons);
+            assert_eq!(
+                uf.largest(3),
+                clusters
+                    .iter()
+                    .map(|it| it.len())
+                    .chain(std::iter::repeat_n(1, singletons))
+                    .sorted()
+                    .rev()
+                    .take(3)
+                    .collect_vec()
+            );
+        }
+    }
+
+    #[test]
+    fn tracks_sizes_and_members() {
+        let mut uf = UnionFind::new(6);
+        assert!(uf.union(0, 1));
+        assert!(uf.union(2, 1));
+        assert!(!uf.union(0, 2));
+        assert!(uf.union(4, 5));
+
+        assert_eq!(uf.component_count(), 3);
+        assert_eq!(uf.size_of(2), 3);
+        assert!(uf.connected(0, 2));
+        assert!(!uf.connected(3, 4));
+        assert_eq!(uf.members(1), vec![0, 1, 2]);
+        assert_eq!(uf.largest(2), vec![3, 2]);
+        assert_eq!(uf.component_sizes().iter().sum::<usize>(), 6);
+    }
+}